@@ -0,0 +1,50 @@
+// Firmware and other #![no_std] crates cannot name anything under std::, so
+// the generated builder needs a mode that refers only to core:: and alloc::
+// items.
+//
+// With #[builder(no_std)] on the struct, generate code that uses absolute
+// core:: and alloc:: paths, and have `build` return a generated error type
+// that implements core::fmt::Display instead of Box<dyn std::error::Error>.
+//
+//     impl CommandBuilder {
+//         pub fn build(&mut self) -> core::result::Result<Command, CommandBuilderError> {
+//             ...
+//         }
+//     }
+//
+// This test binary links std under a different name so that any leftover std::
+// path in the expanded code fails to resolve.
+
+#![no_std]
+
+extern crate alloc;
+extern crate std as host;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(no_std)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_string())
+        .arg("build".to_string())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, ["build"]);
+    assert!(command.current_dir.is_none());
+
+    let err = Command::builder().build().err().unwrap();
+    assert_eq!(err.to_string(), "expected 'executable' to have been set");
+
+    let _ = host::process::id();
+}
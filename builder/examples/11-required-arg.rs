@@ -0,0 +1,47 @@
+// Some fields are always required, and it reads better to pass them to the
+// constructor than to chain a setter and then check at build time that it was
+// called.
+//
+// Look for a field attribute #[builder(required_arg)]. Fields with this
+// attribute become positional parameters of `builder`, in declaration order,
+// and are stored directly in the builder rather than behind an Option. Their
+// setters and `each` setters keep working so they can still be overridden or
+// extended before calling `build`.
+//
+//     impl Command {
+//         pub fn builder(executable: String, args: Vec<String>) -> CommandBuilder {
+//             ...
+//         }
+//     }
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(required_arg)]
+    executable: String,
+    #[builder(required_arg, each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder("cargo".to_owned(), vec!["build".to_owned()])
+        .arg("--release".to_owned())
+        .env(vec![])
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, vec!["build", "--release"]);
+
+    let command = Command::builder("cargo".to_owned(), vec![])
+        .executable("rustc".to_owned())
+        .env(vec![])
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, "rustc");
+    assert!(command.args.is_empty());
+}
@@ -0,0 +1,40 @@
+// The crate using #[builder(no_std)] does not have to declare `extern crate
+// alloc` itself. Whenever the generated code needs alloc:: paths, for the
+// `Vec` behind an `each` setter, it brings the alloc crate into scope on its
+// own, for `builder()` as well as for the setters and `build`.
+//
+// As in the previous test, std is linked under a different name so that any
+// std:: path in the expanded code fails to resolve.
+
+#![no_std]
+
+extern crate std as host;
+
+use derive_builder::Builder;
+use host::string::{String, ToString};
+use host::vec::Vec;
+
+#[derive(Builder)]
+#[builder(no_std)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_string())
+        .arg("build".to_string())
+        .arg("--release".to_string())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, ["build", "--release"]);
+
+    let command = Command::builder()
+        .executable("rustc".to_string())
+        .build()
+        .unwrap();
+    assert!(command.args.is_empty());
+}
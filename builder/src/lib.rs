@@ -1,7 +1,7 @@
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use syn::{
-    parse_macro_input, AngleBracketedGenericArguments, Data, DeriveInput, Field, Fields,
+    parse_macro_input, AngleBracketedGenericArguments, Attribute, Data, DeriveInput, Field, Fields,
     GenericArgument, Ident, LitStr, PathArguments, Type,
 };
 
@@ -9,50 +9,148 @@ use syn::{
 pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
+    let no_std = match input
+        .attrs
+        .iter()
+        .map(outer_builder_attr)
+        .collect::<syn::Result<Vec<_>>>()
+    {
+        Ok(no_std) => no_std.into_iter().any(|no_std| no_std),
+        Err(err) => {
+            let err = err.to_compile_error();
+            return quote! {
+                #err
+            }
+            .into();
+        }
+    };
+
     let name = input.ident;
     let builder = format_ident!("{}Builder", name);
+    let builder_error = format_ident!("{}BuilderError", name);
 
     let bad_attrs: Vec<TokenStream> = fields(&input.data).filter_map(has_bad_attribute).collect();
-    if bad_attrs.len() > 0 {
+    if !bad_attrs.is_empty() {
         return quote! {
             #(#bad_attrs)*
         }
         .into();
     }
 
-    let fields_decl = fields(&input.data).map(field_decl);
-    let fields_default = fields(&input.data).map(field_default);
-    let fields_setter = fields(&input.data).filter_map(field_setter);
-    let fields_each_setter = fields(&input.data).filter_map(field_each_setter);
-    let build_fields = fields(&input.data).map(build_field);
+    let paths = Paths::new(no_std);
+    let Paths { core, .. } = &paths;
 
-    quote! {
-        impl #name {
-            pub fn builder() -> #builder {
-                #builder {
-                    #(#fields_default),*
+    let required_args = fields(&input.data).filter_map(required_arg);
+    let fields_decl = fields(&input.data).map(|field| field_decl(field, &paths));
+    let fields_default = fields(&input.data).map(|field| field_default(field, &paths));
+    let fields_setter = fields(&input.data).filter_map(|field| field_setter(field, &paths));
+    let fields_each_setter =
+        fields(&input.data).filter_map(|field| field_each_setter(field, &paths));
+    let build_fields =
+        fields(&input.data).map(|field| build_field(field, no_std.then_some(&builder_error)));
+
+    let (error_ty, error_decl) = if no_std {
+        (
+            quote!(#builder_error),
+            quote! {
+                #[derive(core::fmt::Debug)]
+                pub struct #builder_error {
+                    message: &'static str,
                 }
-            }
-        }
 
+                impl #builder_error {
+                    fn new(message: &'static str) -> Self {
+                        #builder_error { message }
+                    }
+                }
+
+                impl core::fmt::Display for #builder_error {
+                    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                        f.write_str(self.message)
+                    }
+                }
+            },
+        )
+    } else {
+        (quote!(std::boxed::Box<dyn std::error::Error>), quote!())
+    };
+    let extern_alloc = (no_std
+        && fields(&input.data).any(|field| get_builder_attr_each(field).is_some()))
+    .then(|| {
+        quote!(
+            extern crate alloc;
+        )
+    });
+
+    quote! {
         pub struct #builder {
             #(#fields_decl),*
         }
 
-        impl #builder {
-            pub fn build(&mut self) -> std::result::Result<#name, std::boxed::Box<dyn std::error::Error>> {
-                std::result::Result::Ok(#name {
-                    #(#build_fields),*
-                })
+        #error_decl
+
+        const _: () = {
+            #extern_alloc
+
+            impl #name {
+                pub fn builder(#(#required_args),*) -> #builder {
+                    #builder {
+                        #(#fields_default),*
+                    }
+                }
             }
 
-            #(#fields_setter)*
-            #(#fields_each_setter)*
-        }
+            impl #builder {
+                pub fn build(&mut self) -> #core::result::Result<#name, #error_ty> {
+                    #core::result::Result::Ok(#name {
+                        #(#build_fields),*
+                    })
+                }
+
+                #(#fields_setter)*
+                #(#fields_each_setter)*
+            }
+        };
     }
     .into()
 }
 
+struct Paths {
+    core: TokenStream,
+    alloc: TokenStream,
+}
+
+impl Paths {
+    fn new(no_std: bool) -> Self {
+        if no_std {
+            Paths {
+                core: quote!(core),
+                alloc: quote!(alloc),
+            }
+        } else {
+            Paths {
+                core: quote!(std),
+                alloc: quote!(std),
+            }
+        }
+    }
+}
+
+fn outer_builder_attr(attr: &Attribute) -> syn::Result<bool> {
+    let mut no_std = false;
+    if attr.path().is_ident("builder") {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("no_std") {
+                no_std = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `builder(no_std)`"))
+            }
+        })?;
+    }
+    Ok(no_std)
+}
+
 fn fields(data: &Data) -> impl Iterator<Item = &Field> {
     match data {
         Data::Struct(data) => match &data.fields {
@@ -63,40 +161,53 @@ fn fields(data: &Data) -> impl Iterator<Item = &Field> {
     }
 }
 
+#[derive(Default)]
+struct BuilderAttrs {
+    each: Option<LitStr>,
+    required_arg: bool,
+}
+
 fn has_bad_attribute(field: &Field) -> Option<TokenStream> {
-    get_builder_attr_each_detail(field)
+    get_builder_attrs_detail(field)
         .err()
         .map(syn::Error::into_compile_error)
 }
 
 fn get_builder_attr_each(field: &Field) -> Option<LitStr> {
-    get_builder_attr_each_detail(field).ok().flatten()
+    get_inner_ty(field, "Vec")?;
+    get_builder_attrs_detail(field).ok()?.each
 }
 
-fn get_builder_attr_each_detail(field: &Field) -> syn::Result<Option<LitStr>> {
-    if get_inner_ty(field, "Vec").is_none() {
-        return Ok(None);
-    }
-    if field.attrs.len() != 1 {
-        return Ok(None);
-    }
-    let attr = field.attrs.first().unwrap();
-    if !attr.path().is_ident("builder") {
-        return Ok(None);
-    }
+fn is_required_arg(field: &Field) -> bool {
+    get_builder_attrs_detail(field).is_ok_and(|attrs| attrs.required_arg)
+}
 
-    let mut s: Option<LitStr> = None;
-    attr.parse_nested_meta(|meta| {
-        if meta.path.is_ident("each") {
-            let value = meta.value()?;
-            s = Some(value.parse::<LitStr>()?);
-            Ok(())
-        } else {
-            Err(meta.error("expected `builder(each = \"...\")`"))
+fn get_builder_attrs_detail(field: &Field) -> syn::Result<BuilderAttrs> {
+    let mut attrs = BuilderAttrs::default();
+    for attr in &field.attrs {
+        if !attr.path().is_ident("builder") {
+            continue;
         }
-    })?;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("each") {
+                let value = meta.value()?;
+                attrs.each = Some(value.parse::<LitStr>()?);
+                Ok(())
+            } else if meta.path.is_ident("required_arg") {
+                if get_inner_ty(field, "Option").is_some() {
+                    return Err(
+                        meta.error("`builder(required_arg)` cannot be used on an Option field")
+                    );
+                }
+                attrs.required_arg = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `builder(each = \"...\")` or `builder(required_arg)`"))
+            }
+        })?;
+    }
 
-    Ok(s)
+    Ok(attrs)
 }
 
 fn get_inner_ty<'a>(field: &'a Field, outer: &str) -> Option<&'a syn::Type> {
@@ -123,31 +234,47 @@ fn get_inner_ty<'a>(field: &'a Field, outer: &str) -> Option<&'a syn::Type> {
     None
 }
 
-fn field_decl(field: &Field) -> TokenStream {
+fn required_arg(field: &Field) -> Option<TokenStream> {
+    let name = field.ident.as_ref()?;
+    let ty = &field.ty;
+    is_required_arg(field).then(|| quote!(#name: #ty))
+}
+
+fn field_decl(field: &Field, Paths { core, .. }: &Paths) -> TokenStream {
     let ty = get_inner_ty(field, "Option").unwrap_or(&field.ty);
     if let Some(name) = &field.ident {
+        if is_required_arg(field) {
+            return quote! {
+                #name: #ty
+            };
+        }
         return quote! {
-            #name: std::option::Option<#ty>
+            #name: #core::option::Option<#ty>
         };
     }
     unimplemented!();
 }
 
-fn field_default(field: &Field) -> TokenStream {
+fn field_default(field: &Field, Paths { core, alloc }: &Paths) -> TokenStream {
     if let Some(name) = &field.ident {
+        if is_required_arg(field) {
+            return quote! {
+                #name
+            };
+        }
         if get_builder_attr_each(field).is_some() {
             return quote! {
-                #name: std::option::Option::Some(std::vec::Vec::new())
+                #name: #core::option::Option::Some(#alloc::vec::Vec::new())
             };
         }
         return quote! {
-            #name: std::option::Option::None
+            #name: #core::option::Option::None
         };
     }
     unimplemented!();
 }
 
-fn field_setter(field: &Field) -> Option<TokenStream> {
+fn field_setter(field: &Field, Paths { core, .. }: &Paths) -> Option<TokenStream> {
     let name = field.ident.as_ref()?;
     if let Some(lit) = get_builder_attr_each(field) {
         if *name == lit.value() {
@@ -155,35 +282,51 @@ fn field_setter(field: &Field) -> Option<TokenStream> {
         }
     }
     let ty = get_inner_ty(field, "Option").unwrap_or(&field.ty);
+    if is_required_arg(field) {
+        return Some(quote! {
+            fn #name(&mut self, #name: #ty) -> &mut Self {
+                self.#name = #name;
+                self
+            }
+        });
+    }
     Some(quote! {
         fn #name(&mut self, #name: #ty) -> &mut Self {
-            self.#name = std::option::Option::Some(#name);
+            self.#name = #core::option::Option::Some(#name);
             self
         }
     })
 }
 
-fn field_each_setter(field: &Field) -> Option<TokenStream> {
+fn field_each_setter(field: &Field, Paths { core, alloc }: &Paths) -> Option<TokenStream> {
     let name = field.ident.as_ref()?;
     let ty = get_inner_ty(field, "Vec")?;
     let lit = get_builder_attr_each(field)?;
     let item_name = Ident::new(&lit.value(), lit.span());
+    if is_required_arg(field) {
+        return Some(quote! {
+            fn #item_name(&mut self, item: #ty) -> &mut Self {
+                self.#name.push(item);
+                self
+            }
+        });
+    }
     Some(quote! {
         fn #item_name(&mut self, item: #ty) -> &mut Self {
-            if let std::option::Option::Some(ref mut #name) = self.#name {
+            if let #core::option::Option::Some(ref mut #name) = self.#name {
                 #name.push(item);
             } else {
-                self.#name = std::option::Option::Some(std::vec![item]);
+                self.#name = #core::option::Option::Some(#alloc::vec![item]);
             }
             self
         }
     })
 }
 
-fn build_field(field: &Field) -> TokenStream {
+fn build_field(field: &Field, error: Option<&Ident>) -> TokenStream {
     let is_optional = get_inner_ty(field, "Option").is_some();
     if let Some(name) = &field.ident {
-        if is_optional {
+        if is_optional || is_required_arg(field) {
             return quote! {
                 #name: self.#name.clone()
             };
@@ -192,8 +335,12 @@ fn build_field(field: &Field) -> TokenStream {
             &format!("expected '{}' to have been set", name),
             name.span(),
         );
+        let err = match error {
+            Some(error) => quote!(#error::new(#err_msg)),
+            None => quote!(#err_msg),
+        };
         return quote! {
-            #name: self.#name.clone().ok_or(#err_msg)?
+            #name: self.#name.clone().ok_or(#err)?
         };
     }
     unimplemented!();
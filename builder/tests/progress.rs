@@ -10,4 +10,7 @@ fn tests() {
     t.pass("examples/07-repeated-field.rs");
     t.compile_fail("examples/08-unrecognized-attribute.rs");
     t.pass("examples/09-redefined-prelude-types.rs");
+    t.pass("examples/10-no-std.rs");
+    t.pass("examples/11-required-arg.rs");
+    t.pass("examples/12-no-std-without-alloc.rs");
 }
@@ -0,0 +1,57 @@
+// CustomDebug should not be limited to structs with named fields. Enums render
+// each variant the way the standard library's derive does: struct variants
+// with debug_struct, tuple variants with debug_tuple, and unit variants by
+// name. Tuple structs and unit structs work the same way.
+//
+// Field attributes and bound inference apply to the fields of every variant.
+//
+//
+// Resources:
+//
+//   - The DebugTuple builder:
+//     https://doc.rust-lang.org/std/fmt/struct.DebugTuple.html
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub enum Event<T> {
+    Key {
+        code: u32,
+        #[debug = "0b{:04b}"]
+        modifiers: u8,
+    },
+    Custom(T, #[debug = "0x{:02x}"] u8),
+    Quit,
+}
+
+#[derive(CustomDebug)]
+pub struct Pair<T>(T, #[debug = "{}ms"] u64);
+
+#[derive(CustomDebug)]
+pub struct Unit;
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    let key = Event::<()>::Key {
+        code: 13,
+        modifiers: 0b0101,
+    };
+    assert_eq!(format!("{:?}", key), "Key { code: 13, modifiers: 0b0101 }");
+
+    let custom = Event::Custom("x", 255);
+    assert_eq!(format!("{:?}", custom), r#"Custom("x", 0xff)"#);
+
+    let quit = Event::<()>::Quit;
+    assert_eq!(format!("{:?}", quit), "Quit");
+
+    let pair = Pair('a', 10);
+    assert_eq!(format!("{:?}", pair), "Pair('a', 10ms)");
+    assert_eq!(format!("{:?}", Unit), "Unit");
+
+    assert_debug::<Never>();
+}
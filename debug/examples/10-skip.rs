@@ -0,0 +1,74 @@
+// Some fields are noise in debug output, or only worth printing when they hold
+// something. Support #[debug(skip)] to leave a field out entirely and
+// #[debug(skip_if = "path")] to leave it out whenever the given predicate
+// returns true for a reference to the field.
+//
+// Whenever a field is left out, render the struct with finish_non_exhaustive
+// so that the output ends in `..` and the reader knows something was omitted.
+//
+// A skipped field is never formatted, so its type must not contribute to the
+// inferred trait bounds. Below, Cache<NotDebug> must still implement Debug.
+//
+//
+// Resources:
+//
+//   - DebugStruct::finish_non_exhaustive:
+//     https://doc.rust-lang.org/std/fmt/struct.DebugStruct.html#method.finish_non_exhaustive
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Cache<T> {
+    name: &'static str,
+    #[debug(skip)]
+    entries: Vec<T>,
+}
+
+#[derive(CustomDebug)]
+pub struct Request {
+    path: &'static str,
+    #[debug(skip_if = "Option::is_none")]
+    body: Option<String>,
+}
+
+#[derive(CustomDebug)]
+pub enum Message {
+    Data(u8, #[debug(skip)] Vec<u8>),
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    assert_debug::<Cache<NotDebug>>();
+
+    let cache = Cache {
+        name: "lru",
+        entries: vec![NotDebug],
+    };
+    assert_eq!(format!("{:?}", cache), r#"Cache { name: "lru", .. }"#);
+    assert_eq!(cache.entries.len(), 1);
+
+    let request = Request {
+        path: "/",
+        body: None,
+    };
+    assert_eq!(format!("{:?}", request), r#"Request { path: "/", .. }"#);
+
+    let request = Request {
+        path: "/",
+        body: Some("{}".to_owned()),
+    };
+    assert_eq!(
+        format!("{:?}", request),
+        r#"Request { path: "/", body: Some("{}") }"#,
+    );
+
+    let message = Message::Data(1, vec![2, 3]);
+    assert_eq!(format!("{:?}", message), "Data(1, ..)");
+
+    let Message::Data(_, payload) = message;
+    assert_eq!(payload, [2, 3]);
+}
@@ -0,0 +1,64 @@
+// A single format string cannot express every rendering. Support
+// #[debug(with = "path::to::function")] where the function has the signature
+//
+//     fn(&FieldType, &mut std::fmt::Formatter) -> std::fmt::Result
+//
+// and is called to format the field in place of its Debug impl. Since the
+// function takes care of formatting, the field's type does not need to
+// implement Debug and must not contribute a Debug bound.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+use std::time::{Duration, SystemTime};
+
+mod fmt_helpers {
+    use std::fmt;
+    use std::time::SystemTime;
+
+    pub fn hex(bytes: &Vec<u8>, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in bytes {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+
+    pub fn unix(time: &SystemTime, f: &mut fmt::Formatter) -> fmt::Result {
+        let secs = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        write!(f, "@{}", secs)
+    }
+}
+
+pub struct Opaque;
+
+fn opaque<T>(_: &T, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("<opaque>")
+}
+
+#[derive(CustomDebug)]
+pub struct Packet<T> {
+    #[debug(with = "fmt_helpers::hex")]
+    payload: Vec<u8>,
+    #[debug(with = fmt_helpers::unix)]
+    received: SystemTime,
+    #[debug(with = "opaque")]
+    context: T,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    assert_debug::<Packet<Opaque>>();
+
+    let packet = Packet {
+        payload: vec![0xde, 0xad, 0xbe, 0xef],
+        received: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        context: Opaque,
+    };
+    assert_eq!(
+        format!("{:?}", packet),
+        "Packet { payload: deadbeef, received: @1700000000, context: <opaque> }",
+    );
+}
@@ -0,0 +1,44 @@
+// All field options live in a single #[debug(...)] grammar which may be spread
+// across any number of attributes, interleaved with doc comments and other
+// attributes:
+//
+//     #[debug(format = "...")]      same as the #[debug = "..."] shorthand
+//     #[debug(skip)]
+//     #[debug(skip_if = "path")]
+//     #[debug(redact)]
+//     #[debug(with = path)]
+//     #[debug(rename = "...")]
+//
+// Unknown keys, repeated keys and options that cannot be combined are reported
+// as errors pointing at the offending key; see the next test case.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Register {
+    /// Name shown in the datasheet.
+    #[debug(rename = "register")]
+    name: &'static str,
+    /// Raw bits.
+    #[debug = "0b{:08b}"]
+    #[allow(dead_code)]
+    bits: u8,
+    #[debug(format = "0x{:04x}", rename = "addr")]
+    address: u16,
+    #[debug = "{{{0:>4}|{0:<4}}}"]
+    flags: u8,
+}
+
+fn main() {
+    let register = Register {
+        name: "CTRL",
+        bits: 0b1000_0001,
+        address: 0x40,
+        flags: 7,
+    };
+
+    assert_eq!(
+        format!("{:?}", register),
+        r#"Register { register: "CTRL", bits: 0b10000001, addr: 0x0040, flags: {   7|7   } }"#,
+    );
+}
@@ -0,0 +1,60 @@
+// Mistakes in #[debug(...)] attributes are compile errors that point at the
+// key responsible rather than at the derive as a whole.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Unknown {
+    #[debug(skpi)]
+    field: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Duplicate {
+    #[debug = "{:x}"]
+    #[debug(format = "{:b}")]
+    field: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Conflict {
+    #[debug(skip, redact)]
+    field: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "T: Copy", sort)]
+pub struct UnknownOuter<T> {
+    field: T,
+}
+
+#[derive(CustomDebug)]
+pub struct TwoPresets {
+    #[debug(hex, truncate = 4)]
+    field: Vec<u8>,
+}
+
+#[derive(CustomDebug)]
+#[debug(sort_fields)]
+pub struct SortedFlatten {
+    title: u8,
+    #[debug(flatten)]
+    meta: Meta,
+}
+
+#[derive(CustomDebug)]
+pub struct Meta {
+    author: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct FmtNotLast {
+    #[debug(fmt = "{}/{}", self.used, self.cap, rename = "usage")]
+    usage: (),
+    #[debug(skip)]
+    used: usize,
+    #[debug(skip)]
+    cap: usize,
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+// Format strings in #[debug = "..."] are checked during macro expansion. Each
+// must format the field exactly once, as `{}` or `{0}` with any format spec,
+// and mistakes are reported against the string literal itself instead of
+// surfacing as a confusing error from inside the generated format_args!.
+//
+// Subspans of a literal can only be resolved on a nightly compiler, so on
+// stable the error points at the whole string.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct TooMany {
+    #[debug = "{} {}"]
+    field: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct UnknownTrait {
+    #[debug = "{:q}"]
+    field: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Named {
+    #[debug = "{value:x}"]
+    field: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Unmatched {
+    #[debug(format = "{:x")]
+    field: u8,
+}
+
+fn main() {}
@@ -0,0 +1,116 @@
+// Sometimes a field only makes sense next to its siblings. Support
+//
+//     #[debug(fmt = "{}/{}", self.used, self.capacity)]
+//
+// on a field, formatting the field with the given format string and argument
+// expressions, which may refer to `self`. The arguments run to the end of the
+// attribute, so `fmt` has to be the last key in its #[debug(...)]. In an enum,
+// `self.width` is the `width` field of the variant being formatted, and a
+// field can be referred to this way even if it is skipped.
+//
+// On the struct itself, #[debug(fmt = "Point({x}, {y})")] replaces the
+// debug_struct rendering entirely, interpolating fields by name (or by index
+// for tuple structs).
+//
+// Bounds are only inferred for fields that are actually formatted, using the
+// trait selected by each placeholder: `{x}` needs Display, `{x:?}` needs
+// Debug, `{x:x}` needs LowerHex, and so on.
+
+use derive_debug::CustomDebug;
+use std::fmt::{Debug, Display};
+
+#[derive(CustomDebug)]
+pub struct Buffer {
+    #[debug(fmt = "{}/{} bytes", self.used, self.capacity)]
+    ptr: *const u8,
+    used: usize,
+    capacity: usize,
+}
+
+#[derive(CustomDebug)]
+pub enum Shape {
+    Rect {
+        #[debug(fmt = "{}x{}", self.width, self.height)]
+        size: (),
+        #[debug(skip)]
+        width: u32,
+        #[debug(skip)]
+        height: u32,
+    },
+}
+
+#[derive(CustomDebug)]
+pub enum Slot<T> {
+    Full {
+        #[debug(fmt = "{}", self.value)]
+        tag: (),
+        #[debug(skip)]
+        value: T,
+    },
+    Empty,
+}
+
+#[derive(CustomDebug)]
+#[debug(fmt = "Point({x}, {y:?})")]
+pub struct Point<X, Y, Z> {
+    x: X,
+    y: Y,
+    z: Z,
+}
+
+#[derive(CustomDebug)]
+#[debug(fmt = "{0:#x}m")]
+pub struct Meters<T>(T);
+
+fn assert_debug<F: Debug>() {}
+
+fn assert_point_debug<X: Display, Y: Debug, Z>() {
+    assert_debug::<Point<X, Y, Z>>();
+}
+
+fn main() {
+    struct NotDebug;
+
+    let buffer = Buffer {
+        ptr: std::ptr::null(),
+        used: 3,
+        capacity: 8,
+    };
+    assert_eq!(
+        format!("{:?}", buffer),
+        "Buffer { ptr: 3/8 bytes, used: 3, capacity: 8 }",
+    );
+
+    let shape = Shape::Rect {
+        size: (),
+        width: 4,
+        height: 3,
+    };
+    assert_eq!(format!("{:?}", shape), "Rect { size: 4x3, .. }");
+
+    struct Celsius(i8);
+
+    impl Display for Celsius {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}°C", self.0)
+        }
+    }
+
+    let slot = Slot::Full {
+        tag: (),
+        value: Celsius(21),
+    };
+    assert_eq!(format!("{:?}", slot), "Full { tag: 21°C, .. }");
+    assert_eq!(format!("{:?}", Slot::<Celsius>::Empty), "Empty");
+
+    let point = Point {
+        x: 1.5,
+        y: "up",
+        z: NotDebug,
+    };
+    assert_eq!(format!("{:?}", point), r#"Point(1.5, "up")"#);
+    assert_point_debug::<f64, &str, NotDebug>();
+
+    assert_eq!(format!("{:?}", Meters(255)), "0xffm");
+    let _ = (buffer.ptr, point.z);
+}
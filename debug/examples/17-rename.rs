@@ -0,0 +1,69 @@
+// Debug output is easier to correlate with other tooling when it uses the same
+// names. Support #[debug(rename = "...")] on fields, enum variants and the type
+// itself, and a type-level #[debug(rename_all = "...")] that converts every
+// field name with one of the rules
+//
+//     "lowercase", "UPPERCASE", "PascalCase", "camelCase", "snake_case",
+//     "SCREAMING_SNAKE_CASE", "kebab-case", "SCREAMING-KEBAB-CASE"
+//
+// An explicit field rename takes precedence over rename_all.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(rename = "Session", rename_all = "camelCase")]
+pub struct SessionState {
+    user_id: u32,
+    last_seen_at: u64,
+    #[debug(rename = "TTL")]
+    time_to_live: u32,
+}
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum Frame {
+    #[debug(rename = "HEADERS")]
+    Headers {
+        stream_id: u32,
+        end_stream: bool,
+    },
+    Data(Vec<u8>),
+}
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "PascalCase")]
+pub struct Header {
+    content_type: &'static str,
+    x_request_id: u64,
+}
+
+fn main() {
+    let state = SessionState {
+        user_id: 7,
+        last_seen_at: 1_700_000_000,
+        time_to_live: 60,
+    };
+    assert_eq!(
+        format!("{:?}", state),
+        "Session { userId: 7, lastSeenAt: 1700000000, TTL: 60 }",
+    );
+
+    let frame = Frame::Headers {
+        stream_id: 1,
+        end_stream: true,
+    };
+    assert_eq!(
+        format!("{:?}", frame),
+        "HEADERS { STREAM-ID: 1, END-STREAM: true }",
+    );
+    assert_eq!(format!("{:?}", Frame::Data(vec![1])), "Data([1])");
+
+    let header = Header {
+        content_type: "text/plain",
+        x_request_id: 42,
+    };
+    assert_eq!(
+        format!("{:?}", header),
+        "Header { ContentType: \"text/plain\", XRequestId: 42 }",
+    );
+}
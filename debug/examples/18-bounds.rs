@@ -0,0 +1,86 @@
+// Extends the escape hatch from test 08.
+//
+// Any number of #[debug(bound = "...")] predicates may be given on the type,
+// either comma-separated in one string or as repeated attributes, and
+// #[debug(bound = "")] turns off bound inference altogether.
+//
+// On a field, #[debug(bound = "...")] replaces only the bounds that would have
+// been inferred from that field's type, leaving the inference for the other
+// fields in place.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub trait Trait {
+    type Value;
+    type Error;
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "T::Value: Debug, T::Error: Debug")]
+#[debug(bound = "U: Debug")]
+pub struct Multiple<T: Trait, U> {
+    values: Vec<T::Value>,
+    errors: Vec<T::Error>,
+    extra: U,
+}
+
+#[derive(CustomDebug)]
+pub struct PerField<T: Trait, U> {
+    #[debug(bound = "T::Value: Debug")]
+    field: Field<T>,
+    normal: U,
+}
+
+#[derive(CustomDebug)]
+struct Field<T: Trait> {
+    values: Vec<T::Value>,
+}
+
+// Implements Debug for every T, which inference cannot know.
+pub struct Handle<T>(Vec<T>);
+
+impl<T> Debug for Handle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Handle({})", self.0.len())
+    }
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "")]
+pub struct NoInference<T> {
+    handle: Handle<T>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct Id;
+    struct NotDebug;
+
+    impl Trait for Id {
+        type Value = u8;
+        type Error = String;
+    }
+
+    assert_debug::<Multiple<Id, u8>>();
+    assert_debug::<PerField<Id, u8>>();
+    assert_debug::<NoInference<NotDebug>>();
+
+    let per_field = PerField::<Id, _> {
+        field: Field { values: vec![1, 2] },
+        normal: 'x',
+    };
+    assert_eq!(
+        format!("{:?}", per_field),
+        "PerField { field: Field { values: [1, 2] }, normal: 'x' }",
+    );
+
+    let no_inference = NoInference {
+        handle: Handle(vec![NotDebug]),
+    };
+    assert_eq!(
+        format!("{:?}", no_inference),
+        "NoInference { handle: Handle(1) }",
+    );
+}
@@ -0,0 +1,66 @@
+// Bound inference looks at the whole type of every formatted field, not just
+// its outermost generic arguments.
+//
+// Wherever a type parameter shows up on its own it receives a `Debug` bound,
+// and wherever one of its associated types shows up (`T::Value`, or the fully
+// qualified `<T as Trait>::Value`) a where-clause predicate is emitted for that
+// associated type instead. Occurrences inside PhantomData, function pointers
+// and raw pointers do not need the parameter to be Debug, so none of those
+// produce a bound.
+
+use derive_debug::CustomDebug;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+pub trait Trait {
+    type Value;
+    type Item;
+}
+
+#[derive(CustomDebug)]
+pub struct Nested<'a, K, T: Trait, U, V> {
+    map: HashMap<K, T::Value>,
+    boxed: Box<Option<<T as Trait>::Item>>,
+    pair: (U, u8),
+    array: [T::Item; 2],
+    reference: &'a V,
+    marker: PhantomData<fn() -> T>,
+}
+
+struct NotDebug;
+
+struct Id;
+
+impl Trait for Id {
+    type Value = String;
+    type Item = u8;
+}
+
+impl Trait for NotDebug {
+    type Value = bool;
+    type Item = char;
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // T is only reachable through its associated types and PhantomData.
+    assert_debug::<Nested<i32, NotDebug, u8, u8>>();
+
+    let mut map = HashMap::new();
+    map.insert(1, "one".to_owned());
+    let v = 7u32;
+    let nested: Nested<i32, Id, &str, u32> = Nested {
+        map,
+        boxed: Box::new(Some(5)),
+        pair: ("pair", 0),
+        array: [1, 2],
+        reference: &v,
+        marker: PhantomData,
+    };
+    let debug = format!("{:?}", nested);
+    assert!(debug.starts_with(
+        r#"Nested { map: {1: "one"}, boxed: Some(5), pair: ("pair", 0), array: [1, 2], reference: 7, marker: PhantomData<fn() -> "#
+    ));
+}
@@ -0,0 +1,98 @@
+// Lifetimes, const generics and defaulted type parameters are carried over to
+// the generated impl untouched, and a `[T; N]` field only puts a bound on its
+// element type.
+//
+// Any where-clause and inline bounds already on the type are kept alongside
+// the inferred ones, and `?Sized` type parameters work whether they sit behind
+// a reference, in a Box, or as the trailing field itself.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Ring<'a, T, const N: usize> {
+    name: &'a str,
+    slots: [T; N],
+    head: usize,
+}
+
+#[derive(CustomDebug)]
+pub struct Defaulted<T = u8, const N: usize = 2> {
+    values: [T; N],
+}
+
+#[derive(CustomDebug)]
+pub struct Unsized<'a, T: ?Sized, U: ?Sized> {
+    borrowed: &'a T,
+    boxed: Box<U>,
+}
+
+#[derive(CustomDebug)]
+pub struct TrailingUnsized<T: ?Sized> {
+    len: usize,
+    tail: T,
+}
+
+#[derive(CustomDebug)]
+pub struct Existing<'a, 'b: 'a, T>
+where
+    T: Clone + 'b,
+    &'a T: Copy,
+{
+    value: &'a T,
+    other: &'b str,
+}
+
+#[derive(CustomDebug)]
+pub struct Inline<T: Clone + Debug, U>
+where
+    U: Default,
+{
+    t: T,
+    u: U,
+}
+
+fn main() {
+    let ring = Ring {
+        name: "r",
+        slots: [1, 2, 3],
+        head: 0,
+    };
+    assert_eq!(
+        format!("{:?}", ring),
+        r#"Ring { name: "r", slots: [1, 2, 3], head: 0 }"#,
+    );
+
+    let defaulted: Defaulted = Defaulted { values: [1, 2] };
+    assert_eq!(format!("{:?}", defaulted), "Defaulted { values: [1, 2] }");
+
+    let unsized_fields: Unsized<str, [u8]> = Unsized {
+        borrowed: "x",
+        boxed: Box::new([1, 2]),
+    };
+    assert_eq!(
+        format!("{:?}", unsized_fields),
+        r#"Unsized { borrowed: "x", boxed: [1, 2] }"#,
+    );
+
+    let trailing: &TrailingUnsized<[u8]> = &TrailingUnsized {
+        len: 2,
+        tail: [1, 2],
+    };
+    assert_eq!(
+        format!("{:?}", trailing),
+        "TrailingUnsized { len: 2, tail: [1, 2] }",
+    );
+
+    let existing = Existing {
+        value: &1,
+        other: "o",
+    };
+    assert_eq!(
+        format!("{:?}", existing),
+        r#"Existing { value: 1, other: "o" }"#,
+    );
+
+    let inline = Inline { t: 1, u: 2 };
+    assert_eq!(format!("{:?}", inline), "Inline { t: 1, u: 2 }");
+}
@@ -0,0 +1,94 @@
+// Alongside CustomDebug, the crate provides a derive for Display.
+//
+// A struct carries a single #[display("...")] template whose placeholders name
+// its fields, or index them in the case of a tuple struct. On an enum, every
+// variant carries its own template; unit variants may leave theirs out and
+// display as the variant name.
+//
+// Format specs pick the trait each field is formatted with, and type
+// parameters are bounded accordingly, the same way CustomDebug infers its
+// bounds.
+
+use derive_debug::CustomDisplay;
+use std::fmt::Display;
+
+#[derive(CustomDisplay)]
+#[display("{host}:{port}")]
+pub struct Address {
+    host: String,
+    port: u16,
+}
+
+#[derive(CustomDisplay)]
+#[display("#{0:08x}")]
+pub struct Id(u32);
+
+#[derive(CustomDisplay)]
+pub enum Error {
+    #[display("could not connect to {addr} after {attempts} attempts")]
+    Connect {
+        addr: Address,
+        attempts: usize,
+    },
+    #[display("request {0} timed out")]
+    Timeout(Id),
+    Cancelled,
+}
+
+#[derive(CustomDisplay)]
+#[display("{label} = {value}")]
+pub struct Labeled<T> {
+    label: &'static str,
+    value: T,
+    #[allow(dead_code)]
+    hidden: std::marker::PhantomData<fn(T)>,
+}
+
+pub struct NotDisplay;
+
+#[derive(CustomDisplay)]
+#[display("{hex:x}")]
+pub struct Hex<T, U> {
+    hex: T,
+    #[allow(dead_code)]
+    unused: U,
+}
+
+fn assert_display<F: Display>() {}
+
+fn main() {
+    let addr = Address {
+        host: "localhost".to_owned(),
+        port: 8080,
+    };
+    assert_eq!(addr.to_string(), "localhost:8080");
+
+    assert_eq!(Id(255).to_string(), "#000000ff");
+
+    let connect = Error::Connect { addr, attempts: 3 };
+    assert_eq!(
+        connect.to_string(),
+        "could not connect to localhost:8080 after 3 attempts",
+    );
+    assert_eq!(
+        Error::Timeout(Id(7)).to_string(),
+        "request #00000007 timed out"
+    );
+    assert_eq!(Error::Cancelled.to_string(), "Cancelled");
+
+    let labeled = Labeled {
+        label: "answer",
+        value: 42,
+        hidden: std::marker::PhantomData,
+    };
+    assert_eq!(labeled.to_string(), "answer = 42");
+
+    // Only the fields named in the template are bounded, with the trait their
+    // spec asks for.
+    assert_display::<Hex<u8, NotDisplay>>();
+    let hex = Hex {
+        hex: 0xabu8,
+        unused: NotDisplay,
+    };
+    assert_eq!(hex.to_string(), "ab");
+}
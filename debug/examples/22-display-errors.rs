@@ -0,0 +1,30 @@
+// Mistakes in CustomDisplay templates are reported at the attribute that
+// contains them: a missing template, a placeholder naming a field that does not
+// exist, or a template placed somewhere it would have no effect.
+
+use derive_debug::CustomDisplay;
+
+#[derive(CustomDisplay)]
+pub struct Missing {
+    value: u8,
+}
+
+#[derive(CustomDisplay)]
+#[display("{host}:{prot}")]
+pub struct Typo {
+    host: String,
+    port: u16,
+}
+
+#[derive(CustomDisplay)]
+pub enum Variant {
+    #[display("ok")]
+    Ok,
+    Failed(String),
+}
+
+#[derive(CustomDisplay)]
+#[display("{0}")]
+pub struct OnField(#[display("{}")] u8);
+
+fn main() {}
@@ -0,0 +1,97 @@
+// Presets for the formats that come up most often in logs.
+//
+// #[debug(hex)] and #[debug(bin)] print an integer with its `0x` or `0b`
+// prefix. On a collection of integers they apply to every element: anything
+// whose reference implements IntoIterator, such as a Vec, HashSet, array or
+// slice, or that derefs to one, such as Box<[T]> or Arc<[T]>.
+//
+// #[debug(bytes)] prints anything that is `AsRef<[u8]>` as an escaped byte
+// string literal.
+//
+// #[debug(truncate = N)] prints the first N elements of any collection and
+// then the number of elements left out, so large buffers don't flood the
+// output.
+
+use derive_debug::CustomDebug;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::Arc;
+
+#[derive(CustomDebug)]
+pub struct Packet<'a, T> {
+    #[debug(hex)]
+    id: u32,
+    #[debug(bin)]
+    flags: u8,
+    #[debug(hex)]
+    checksum: [u8; 4],
+    #[debug(hex)]
+    header: &'a [u16],
+    #[debug(bin)]
+    masks: Vec<T>,
+    #[debug(hex)]
+    mask: T,
+    #[debug(hex)]
+    words: Box<[u32]>,
+    #[debug(bin)]
+    ports: HashSet<u16>,
+    #[debug(hex)]
+    ids: BTreeSet<u16>,
+    #[debug(hex)]
+    shared: Arc<[u8]>,
+    #[debug(bytes)]
+    payload: Vec<u8>,
+    #[debug(bytes)]
+    magic: [u8; 4],
+    #[debug(truncate = 3)]
+    samples: Vec<i32>,
+    #[debug(truncate = 1)]
+    headers: BTreeMap<&'static str, &'static str>,
+    #[debug(truncate = 5)]
+    short: Vec<u8>,
+}
+
+fn main() {
+    let mut headers = BTreeMap::new();
+    headers.insert("accept", "*/*");
+    headers.insert("host", "example.com");
+
+    let packet = Packet {
+        id: 0xdead_beef,
+        flags: 0b101,
+        checksum: [0x12, 0x34, 0xab, 0xcd],
+        header: &[1, 0xffff],
+        masks: vec![1u8, 2],
+        mask: 0xff,
+        words: Box::new([0x10, 0x20]),
+        ports: HashSet::from([3]),
+        ids: BTreeSet::from([0x30, 0x10]),
+        shared: Arc::from(&[0xaa, 0xbb][..]),
+        payload: b"GET /\r\n\xff".to_vec(),
+        magic: *b"\"RS\"",
+        samples: (0..10).collect(),
+        headers,
+        short: vec![1, 2],
+    };
+
+    assert_eq!(
+        format!("{:?}", packet),
+        concat!(
+            "Packet { ",
+            "id: 0xdeadbeef, ",
+            "flags: 0b101, ",
+            "checksum: [0x12, 0x34, 0xab, 0xcd], ",
+            "header: [0x1, 0xffff], ",
+            "masks: [0b1, 0b10], ",
+            "mask: 0xff, ",
+            "words: [0x10, 0x20], ",
+            "ports: [0b11], ",
+            "ids: [0x10, 0x30], ",
+            "shared: [0xaa, 0xbb], ",
+            r#"payload: b"GET /\r\n\xff", "#,
+            r#"magic: b"\"RS\"", "#,
+            "samples: [0, 1, 2, … (7 more)], ",
+            r#"headers: [("accept", "*/*"), … (1 more)], "#,
+            "short: [1, 2] }",
+        ),
+    );
+}
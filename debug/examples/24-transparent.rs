@@ -0,0 +1,49 @@
+// Newtypes whose Debug output should be that of the value they wrap.
+//
+// #[debug(transparent)] forwards straight to the one field that is not
+// skipped, passing along the formatter's flags so that `{:#?}`, width and
+// precision behave as they would on the field itself. Field attributes such as
+// `redact` or `hex` still apply to that field.
+//
+// It is an error to use `transparent` on a struct with more or fewer than one
+// non-skipped field.
+
+use derive_debug::CustomDebug;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct UserId(u64);
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Tagged<T, Tag> {
+    value: T,
+    #[debug(skip)]
+    tag: PhantomData<Tag>,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Token(#[debug(redact)] String);
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Flags(#[debug(hex)] u16);
+
+pub struct NotDebug;
+
+fn main() {
+    assert_eq!(format!("{:?}", UserId(42)), "42");
+    assert_eq!(format!("{:>5?}", UserId(42)), "   42");
+
+    let tagged: Tagged<Vec<u8>, NotDebug> = Tagged {
+        value: vec![1, 2],
+        tag: PhantomData,
+    };
+    assert_eq!(format!("{:?}", tagged), "[1, 2]");
+    assert_eq!(format!("{:#?}", tagged), "[\n    1,\n    2,\n]");
+
+    assert_eq!(format!("{:?}", Token("secret".to_owned())), "[REDACTED]");
+    assert_eq!(format!("{:?}", Flags(0x1f)), "0x1f");
+}
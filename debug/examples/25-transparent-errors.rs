@@ -0,0 +1,27 @@
+// #[debug(transparent)] needs exactly one field to forward to.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Pair(u8, u8);
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct AllSkipped {
+    #[debug(skip)]
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub enum Either {
+    Left(u8),
+    Right(u8),
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent, rename = "Id")]
+pub struct Renamed(u64);
+
+fn main() {}
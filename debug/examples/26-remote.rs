@@ -0,0 +1,118 @@
+// Custom Debug output for types defined in other crates.
+//
+// #[debug(remote = "path::to::Type")] goes on a local copy of the remote type's
+// definition, which may carry any of the usual field attributes. Besides
+// implementing Debug for the copy, the derive generates a `{Name}DebugRemote`
+// wrapper around a reference to the remote type, returned by
+// `Name::debug_remote(&value)`, which formats it the same way.
+//
+// The generated code destructures the remote type against the copy, so a
+// missing or extra field, or one whose type differs, fails to compile instead
+// of silently going out of date.
+//
+// Field attributes behave as they do on a local type. In particular `fmt`
+// arguments like `self.used` refer to the fields of the remote value, even
+// though `self` is the wrapper when formatting through it.
+
+use derive_debug::CustomDebug;
+
+mod other {
+    pub struct Credentials {
+        pub user: String,
+        pub password: String,
+    }
+
+    pub struct Vecish {
+        pub ptr: *const u8,
+        pub used: usize,
+        pub capacity: usize,
+    }
+
+    pub enum Endpoint<T> {
+        Local(u16),
+        Remote { host: String, port: u16, extra: T },
+    }
+}
+
+#[derive(CustomDebug)]
+#[debug(remote = "other::Credentials")]
+pub struct CredentialsDef {
+    user: String,
+    #[debug(redact)]
+    password: String,
+}
+
+#[derive(CustomDebug)]
+#[debug(remote = "other::Vecish")]
+pub struct VecishMirror {
+    #[debug(fmt = "{}/{} bytes", self.used, self.capacity)]
+    ptr: *const u8,
+    used: usize,
+    #[debug(skip)]
+    capacity: usize,
+}
+
+#[derive(CustomDebug)]
+#[debug(remote = "other::Endpoint")]
+pub enum EndpointDef<T> {
+    Local(#[debug(hex)] u16),
+    Remote {
+        host: String,
+        port: u16,
+        #[debug(skip)]
+        extra: T,
+    },
+}
+
+pub struct NotDebug;
+
+pub struct Connection {
+    endpoint: other::Endpoint<NotDebug>,
+}
+
+impl std::fmt::Debug for Connection {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Connection")
+            .field("endpoint", &EndpointDef::debug_remote(&self.endpoint))
+            .finish()
+    }
+}
+
+fn main() {
+    let credentials = other::Credentials {
+        user: "admin".to_owned(),
+        password: "hunter2".to_owned(),
+    };
+    assert_eq!(
+        format!("{:?}", CredentialsDef::debug_remote(&credentials)),
+        r#"Credentials { user: "admin", password: [REDACTED] }"#,
+    );
+
+    let local = other::Endpoint::<()>::Local(255);
+    assert_eq!(
+        format!("{:?}", EndpointDefDebugRemote(&local)),
+        "Local(0xff)",
+    );
+
+    let vecish = other::Vecish {
+        ptr: std::ptr::null(),
+        used: 3,
+        capacity: 8,
+    };
+    assert_eq!(
+        format!("{:?}", VecishMirror::debug_remote(&vecish)),
+        "Vecish { ptr: 3/8 bytes, used: 3, .. }",
+    );
+
+    let connection = Connection {
+        endpoint: other::Endpoint::Remote {
+            host: "example.com".to_owned(),
+            port: 443,
+            extra: NotDebug,
+        },
+    };
+    assert_eq!(
+        format!("{:?}", connection),
+        r#"Connection { endpoint: Remote { host: "example.com", port: 443, .. } }"#,
+    );
+}
@@ -0,0 +1,31 @@
+// A remote mirror that no longer matches the remote definition is rejected.
+
+use derive_debug::CustomDebug;
+
+mod other {
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+
+    pub enum Shape {
+        Circle(u32),
+        Square(u32),
+    }
+}
+
+#[derive(CustomDebug)]
+#[debug(remote = "other::Point")]
+pub struct PointDef {
+    x: i64,
+    y: i32,
+    z: i32,
+}
+
+#[derive(CustomDebug)]
+#[debug(remote = "other::Shape")]
+pub enum ShapeDef {
+    Circle(u32),
+}
+
+fn main() {}
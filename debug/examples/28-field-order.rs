@@ -0,0 +1,102 @@
+// Control over the order and nesting of fields in the output.
+//
+// #[debug(order = N)] moves a field relative to the others. Fields without it
+// count as order 0, and fields of equal order keep their declaration order.
+//
+// #[debug(sort_fields)] on the type lists named fields alphabetically by their
+// displayed name instead; `order` still takes precedence over it.
+//
+// #[debug(flatten)] splices the fields of a nested struct into the parent's
+// output instead of nesting it. The nested struct must itself derive
+// CustomDebug and have named fields. The nested struct writes its fields in its
+// own order, so a type with flattened fields cannot use sort_fields.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Request {
+    body: &'static str,
+    #[debug(order = -1)]
+    id: u32,
+    method: &'static str,
+    #[debug(order = 1)]
+    trace: bool,
+}
+
+#[derive(CustomDebug)]
+#[debug(sort_fields)]
+pub struct Sorted {
+    zone: &'static str,
+    #[debug(rename = "address")]
+    host: &'static str,
+    port: u16,
+    #[debug(order = -1)]
+    version: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Meta<T> {
+    user: T,
+    #[debug(skip_if = "Option::is_none")]
+    session: Option<u64>,
+}
+
+#[derive(CustomDebug)]
+pub struct Event {
+    kind: &'static str,
+    #[debug(flatten)]
+    meta: Meta<&'static str>,
+    #[debug(order = -1)]
+    at: u64,
+}
+
+fn main() {
+    let request = Request {
+        body: "",
+        id: 7,
+        method: "GET",
+        trace: false,
+    };
+    assert_eq!(
+        format!("{:?}", request),
+        r#"Request { id: 7, body: "", method: "GET", trace: false }"#,
+    );
+
+    let sorted = Sorted {
+        zone: "eu",
+        host: "localhost",
+        port: 80,
+        version: 1,
+    };
+    assert_eq!(
+        format!("{:?}", sorted),
+        r#"Sorted { version: 1, address: "localhost", port: 80, zone: "eu" }"#,
+    );
+
+    let event = Event {
+        kind: "login",
+        meta: Meta {
+            user: "ferris",
+            session: Some(3),
+        },
+        at: 1700000000,
+    };
+    assert_eq!(
+        format!("{:?}", event),
+        r#"Event { at: 1700000000, kind: "login", user: "ferris", session: Some(3) }"#,
+    );
+
+    // Fields hidden in the flattened struct mark the parent as non-exhaustive.
+    let event = Event {
+        kind: "logout",
+        meta: Meta {
+            user: "ferris",
+            session: None,
+        },
+        at: 1700000001,
+    };
+    assert_eq!(
+        format!("{:?}", event),
+        r#"Event { at: 1700000001, kind: "logout", user: "ferris", .. }"#,
+    );
+}
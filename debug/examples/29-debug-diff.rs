@@ -0,0 +1,143 @@
+// A derive for comparing two values field by field, for when assert_eq! on a
+// large struct prints two dumps too long to tell apart.
+//
+// #[derive(DebugDiff)] generates `fn debug_diff(&self, other: &Self) ->
+// Option<String>`, which lists the path of every field that differs along
+// with its old and new value. Fields whose type also derives DebugDiff are
+// compared field by field in turn; all others are compared by their Debug
+// output.
+//
+// The #[debug(...)] attributes of CustomDebug are shared: skipped fields are
+// not compared, redacted fields are reported without their values, renamed
+// fields appear under their new name and flattened fields report their own
+// fields directly under the parent. Fields formatted with `with`, `format`,
+// `fmt` or a preset are compared and shown the way CustomDebug renders them,
+// so their type does not need to implement Debug itself.
+
+use derive_debug::{CustomDebug, DebugDiff};
+use std::fmt;
+
+#[derive(CustomDebug, DebugDiff)]
+pub struct Config {
+    name: String,
+    server: Server,
+    #[debug(flatten)]
+    limits: Limits<u32>,
+    #[debug(skip)]
+    cache: Vec<u8>,
+    #[debug(redact)]
+    token: String,
+    #[debug(rename = "mode")]
+    kind: Mode,
+}
+
+#[derive(CustomDebug, DebugDiff)]
+pub struct Server {
+    host: String,
+    ports: Vec<u16>,
+}
+
+#[derive(CustomDebug, DebugDiff)]
+pub struct Limits<T> {
+    max_connections: T,
+    timeout: (T, T),
+}
+
+#[derive(CustomDebug, DebugDiff)]
+pub enum Mode {
+    Dev,
+    Prod { replicas: u8 },
+}
+
+pub struct Raw(u16);
+
+fn raw(raw: &Raw, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "raw:{}", raw.0)
+}
+
+#[derive(CustomDebug, DebugDiff)]
+pub struct Packet {
+    #[debug(with = "raw")]
+    raw: Raw,
+    #[debug(hex)]
+    flags: u8,
+    #[debug(fmt = "{}/{}", self.used, self.capacity)]
+    usage: (),
+    #[debug(skip)]
+    used: usize,
+    #[debug(skip)]
+    capacity: usize,
+}
+
+fn config() -> Config {
+    Config {
+        name: "app".to_owned(),
+        server: Server {
+            host: "localhost".to_owned(),
+            ports: vec![80, 443],
+        },
+        limits: Limits {
+            max_connections: 100,
+            timeout: (5, 30),
+        },
+        cache: Vec::new(),
+        token: "secret".to_owned(),
+        kind: Mode::Prod { replicas: 2 },
+    }
+}
+
+fn main() {
+    assert_eq!(config().debug_diff(&config()), None);
+
+    let mut changed = config();
+    changed.server.ports.push(8080);
+    changed.limits.timeout.1 = 60;
+    changed.cache = vec![1, 2, 3];
+    changed.token = "rotated".to_owned();
+    changed.kind = Mode::Prod { replicas: 3 };
+    assert_eq!(
+        config().debug_diff(&changed).unwrap(),
+        concat!(
+            "server.ports: [80, 443] -> [80, 443, 8080]\n",
+            "timeout: (5, 30) -> (5, 60)\n",
+            "token: [REDACTED] -> [REDACTED]\n",
+            "mode.replicas: 2 -> 3",
+        ),
+    );
+
+    let mut changed = config();
+    changed.name = "other".to_owned();
+    changed.kind = Mode::Dev;
+    assert_eq!(
+        config().debug_diff(&changed).unwrap(),
+        concat!(
+            r#"name: "app" -> "other""#,
+            "\n",
+            "mode: Prod { replicas: 2 } -> Dev",
+        ),
+    );
+
+    let packet = Packet {
+        raw: Raw(1),
+        flags: 0x0f,
+        usage: (),
+        used: 3,
+        capacity: 8,
+    };
+    let changed = Packet {
+        raw: Raw(2),
+        flags: 0xf0,
+        usage: (),
+        used: 4,
+        capacity: 8,
+    };
+    assert_eq!(packet.debug_diff(&packet), None);
+    assert_eq!(
+        packet.debug_diff(&changed).unwrap(),
+        concat!(
+            "raw: raw:1 -> raw:2\n",
+            "flags: 0xf -> 0xf0\n",
+            "usage: 3/8 -> 4/8",
+        ),
+    );
+}
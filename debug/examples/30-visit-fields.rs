@@ -0,0 +1,92 @@
+// Structured access to the fields, for loggers that record key/value pairs
+// rather than one Debug string.
+//
+// #[debug(visit)] additionally implements the DebugFields trait, whose
+// `visit_fields` passes each field to a FieldVisitor by name together with its
+// value as `&dyn Debug`. Exactly the fields that the Debug output would show
+// are visited, in the same order, with renames, redaction and formatting
+// applied. Tuple fields are named by their index.
+
+use derive_debug::{CustomDebug, DebugFields, FieldVisitor};
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+#[debug(visit)]
+pub struct Login {
+    #[debug(rename = "user_name")]
+    user: String,
+    #[debug(redact)]
+    password: String,
+    #[debug(skip)]
+    attempts: u32,
+    #[debug(skip_if = "Option::is_none")]
+    otp: Option<u32>,
+    #[debug(hex)]
+    flags: u8,
+    #[debug(flatten)]
+    client: Client,
+}
+
+#[derive(CustomDebug)]
+#[debug(visit)]
+pub struct Client {
+    ip: &'static str,
+    agent: &'static str,
+}
+
+#[derive(CustomDebug)]
+#[debug(visit)]
+pub enum Event {
+    Logout(u64),
+    Tick,
+}
+
+#[derive(Default)]
+struct Collect(Vec<(String, String)>);
+
+impl FieldVisitor for Collect {
+    fn field(&mut self, name: &str, value: &dyn Debug) {
+        self.0.push((name.to_owned(), format!("{:?}", value)));
+    }
+}
+
+fn fields(value: &dyn DebugFields) -> Vec<(String, String)> {
+    let mut collect = Collect::default();
+    value.visit_fields(&mut collect);
+    collect.0
+}
+
+fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+fn main() {
+    let login = Login {
+        user: "ferris".to_owned(),
+        password: "hunter2".to_owned(),
+        attempts: 1,
+        otp: None,
+        flags: 0x20,
+        client: Client {
+            ip: "127.0.0.1",
+            agent: "curl",
+        },
+    };
+    assert_eq!(login.attempts, 1);
+    assert_eq!(
+        fields(&login),
+        pairs(&[
+            ("user_name", r#""ferris""#),
+            ("password", "[REDACTED]"),
+            ("flags", "0x20"),
+            ("ip", r#""127.0.0.1""#),
+            ("agent", r#""curl""#),
+        ]),
+    );
+
+    assert_eq!(fields(&Event::Logout(9)), pairs(&[("0", "9")]));
+    assert_eq!(fields(&Event::Tick), pairs(&[]));
+}
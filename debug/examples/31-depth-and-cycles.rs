@@ -0,0 +1,74 @@
+// Protection against runaway output from recursive types.
+//
+// #[debug(max_depth = N)] shows at most N levels of nested values of types
+// carrying the attribute, and prints `…` in place of anything deeper. The
+// depth is tracked per thread while formatting, so it also applies through
+// collections and smart pointers in between.
+//
+// #[debug(cycle_safe)] remembers which values are currently being formatted
+// and prints `<cycle>` when one of them is reached again, such as through an
+// Rc<RefCell<..>> pointing back up a graph.
+
+use derive_debug::CustomDebug;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 2)]
+pub struct Tree {
+    value: u32,
+    children: Vec<Tree>,
+}
+
+#[derive(CustomDebug)]
+#[debug(cycle_safe)]
+pub struct Node {
+    name: &'static str,
+    next: Option<Rc<RefCell<Node>>>,
+}
+
+fn leaf(value: u32) -> Tree {
+    Tree {
+        value,
+        children: Vec::new(),
+    }
+}
+
+fn main() {
+    let tree = Tree {
+        value: 1,
+        children: vec![
+            Tree {
+                value: 2,
+                children: vec![leaf(3)],
+            },
+            leaf(4),
+        ],
+    };
+    assert_eq!(
+        format!("{:?}", tree),
+        "Tree { value: 1, children: [Tree { value: 2, children: […] }, Tree { value: 4, children: [] }] }",
+    );
+    // The limit applies from wherever formatting starts.
+    assert_eq!(
+        format!("{:?}", tree.children[0]),
+        "Tree { value: 2, children: [Tree { value: 3, children: [] }] }",
+    );
+
+    let a = Rc::new(RefCell::new(Node {
+        name: "a",
+        next: None,
+    }));
+    let b = Rc::new(RefCell::new(Node {
+        name: "b",
+        next: Some(a.clone()),
+    }));
+    a.borrow_mut().next = Some(b.clone());
+    assert_eq!(
+        format!("{:?}", a.borrow()),
+        r#"Node { name: "a", next: Some(RefCell { value: Node { name: "b", next: Some(RefCell { value: <cycle> }) } }) }"#,
+    );
+
+    // Break the cycle so the nodes are freed.
+    b.borrow_mut().next = None;
+}
@@ -0,0 +1,55 @@
+// Marker fields in any form do not put a Debug bound on the type parameters
+// they mention.
+//
+// PhantomData is recognized under any path, such as std::marker::PhantomData
+// or core::marker::PhantomData, and also when it is nested inside another
+// type. Type parameters only reached through function pointers or raw
+// pointers, like PhantomData<fn() -> T> or PhantomData<*const T>, are treated
+// the same way, since those implement Debug regardless.
+//
+// For marker types defined in your own code, #[debug(phantom)] on the field
+// keeps its type out of bound inference. The field is still printed using the
+// marker type's own Debug impl.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+#[derive(CustomDebug)]
+pub struct Qualified<A, B, C, D, E> {
+    std: std::marker::PhantomData<A>,
+    core: core::marker::PhantomData<fn() -> B>,
+    absolute: ::std::marker::PhantomData<*const C>,
+    nested: Option<PhantomData<(D, E)>>,
+    pointer: *const E,
+}
+
+pub struct Marker<T>(PhantomData<T>);
+
+impl<T> Debug for Marker<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Marker")
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Tagged<T, Tag> {
+    value: T,
+    #[debug(phantom)]
+    tag: Marker<Tag>,
+}
+
+pub struct NotDebug;
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    assert_debug::<Qualified<NotDebug, NotDebug, NotDebug, NotDebug, NotDebug>>();
+    assert_debug::<Tagged<u8, NotDebug>>();
+
+    let tagged: Tagged<u8, NotDebug> = Tagged {
+        value: 1,
+        tag: Marker(PhantomData),
+    };
+    assert_eq!(format!("{:?}", tagged), "Tagged { value: 1, tag: Marker }");
+}
@@ -0,0 +1,37 @@
+// A type-level #[debug(fmt = "...")] formats the fields it names directly, so
+// it cannot honor field attributes that hide or reformat them. Naming such a
+// field is an error rather than a silent leak of a redacted or skipped value.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(fmt = "Login({user}, {token})")]
+pub struct Login {
+    user: String,
+    #[debug(redact)]
+    token: String,
+}
+
+#[derive(CustomDebug)]
+#[debug(fmt = "{user}/{password}")]
+pub struct Skipped {
+    user: String,
+    #[debug(skip)]
+    password: String,
+}
+
+#[derive(CustomDebug)]
+#[debug(fmt = "{id:?}")]
+pub struct Hex {
+    #[debug(hex)]
+    id: u32,
+}
+
+#[derive(CustomDebug)]
+#[debug(redact_all_except(user), fmt = "{user}/{password}")]
+pub struct RedactAll {
+    user: String,
+    password: String,
+}
+
+fn main() {}
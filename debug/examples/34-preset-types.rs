@@ -0,0 +1,15 @@
+// #[debug(hex)] and #[debug(bin)] on a field that is neither an integer nor a
+// collection of integers point at the field's type.
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeMap;
+
+#[derive(CustomDebug)]
+pub struct Packet {
+    #[debug(hex)]
+    name: String,
+    #[debug(bin)]
+    ports: BTreeMap<u16, u16>,
+}
+
+fn main() {}
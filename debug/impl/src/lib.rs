@@ -0,0 +1,2450 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use std::ops::Range;
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::visit_mut::{self as visit_mut, VisitMut};
+use syn::{
+    parenthesized, parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Expr, ExprField,
+    ExprPath, Field, Fields, GenericParam, Generics, Ident, LitInt, LitStr, Member, Meta, Path,
+    PredicateType, Token, Type, TypeBareFn, TypeImplTrait, TypePath, TypePtr, TypeTraitObject,
+    Variant, Visibility, WherePredicate,
+};
+
+#[proc_macro_derive(CustomDebug, attributes(debug))]
+pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let struct_attrs = match struct_attrs(&input.attrs, &input.data) {
+        Ok(struct_attrs) => struct_attrs,
+        Err(err) => {
+            let err = err.to_compile_error();
+            return quote! {
+                #err
+            }
+            .into();
+        }
+    };
+
+    if let Err(err) = fields(&input.data).try_for_each(|field| field_attrs(field).map(drop)) {
+        let err = err.to_compile_error();
+        return quote! {
+            #err
+        }
+        .into();
+    }
+
+    if let Data::Enum(data) = &input.data {
+        if let Err(err) = data
+            .variants
+            .iter()
+            .try_for_each(|variant| variant_attrs(variant).map(drop))
+        {
+            let err = err.to_compile_error();
+            return quote! {
+                #err
+            }
+            .into();
+        }
+    }
+
+    let name = input.ident;
+    if let Data::Union(data) = &input.data {
+        return syn::Error::new(data.union_token.span, "CustomDebug does not support unions")
+            .to_compile_error()
+            .into();
+    }
+
+    let partial_redaction = fields(&input.data)
+        .any(|field| field_attrs(field).is_ok_and(|attrs| attrs.redact == Some(Redact::Partial)))
+        .then(partial_redaction);
+    let debug_with = debug_with_helper(&input.data);
+    let helpers = quote! {
+        #partial_redaction
+        #debug_with
+    };
+    let body = debug_body(
+        &input.data,
+        &struct_attrs,
+        quote!(self),
+        quote!(Self),
+        &name,
+    );
+
+    let formatted = formatted_fields(&input.data, &struct_attrs);
+    let generics = add_trait_bounds(
+        input.generics.clone(),
+        &input.data,
+        &struct_attrs,
+        &formatted,
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // A remote derive also formats the wrapped `self.0` for its wrapper.
+    let remote = struct_attrs.remote.as_ref().map(|remote| {
+        let type_name = &remote.segments.last().unwrap().ident;
+        let body = debug_body(
+            &input.data,
+            &struct_attrs,
+            quote!(self.0),
+            remote.to_token_stream(),
+            type_name,
+        );
+        let body = quote! {
+            #helpers
+            #body
+        };
+        remote_debug(
+            &input.vis,
+            &name,
+            remote,
+            &input.generics,
+            &generics,
+            &input.data,
+            body,
+        )
+    });
+
+    let flatten = flatten_method(&input.data, &struct_attrs, &helpers).map(|method| {
+        quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                #method
+            }
+        }
+    });
+
+    let visit = struct_attrs.visit.then(|| {
+        let method = visit_fields(&input.data, &struct_attrs, &helpers);
+        quote! {
+            impl #impl_generics ::derive_debug::DebugFields for #name #ty_generics #where_clause {
+                #method
+            }
+        }
+    });
+
+    quote! {
+        impl #impl_generics std::fmt::Debug for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                #helpers
+                #body
+            }
+        }
+
+        #flatten
+        #visit
+        #remote
+    }
+    .into()
+}
+
+#[proc_macro_derive(CustomDisplay, attributes(display))]
+pub fn derive_display(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let arms = match display_arms(&input) {
+        Ok(arms) => arms,
+        Err(err) => {
+            let err = err.to_compile_error();
+            return quote! {
+                #err
+            }
+            .into();
+        }
+    };
+
+    let body = if arms.is_empty() {
+        quote! {
+            match *self {}
+        }
+    } else {
+        let arms = arms.iter().map(|arm| match &arm.fmt {
+            Some(fmt) => template_arm(arm.path.clone(), fmt),
+            None => {
+                let path = &arm.path;
+                let lit_name = LitStr::new(&arm.ident.unraw().to_string(), arm.ident.span());
+                quote! {
+                    #path => f.write_str(#lit_name),
+                }
+            }
+        });
+        quote! {
+            match self {
+                #(#arms)*
+            }
+        }
+    };
+
+    let formatted: Vec<_> = arms
+        .iter()
+        .filter_map(|arm| Some(template_fields(arm.fmt.as_ref()?, arm.fields)))
+        .flatten()
+        .collect();
+    let mut generics = input.generics.clone();
+    infer_bounds(&mut generics, &formatted, &[]);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let name = &input.ident;
+
+    quote! {
+        impl #impl_generics std::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                #body
+            }
+        }
+    }
+    .into()
+}
+
+// A struct or an enum variant together with its `#[display("...")]` template.
+// Unit variants may leave the template out and display as their own name.
+struct DisplayArm<'a> {
+    path: TokenStream,
+    ident: &'a Ident,
+    fields: &'a Fields,
+    fmt: Option<LitStr>,
+}
+
+fn display_arms(input: &DeriveInput) -> syn::Result<Vec<DisplayArm<'_>>> {
+    for field in fields(&input.data) {
+        if let Some(attr) = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("display"))
+        {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "`display` goes on the type or on enum variants, not on fields",
+            ));
+        }
+    }
+    match &input.data {
+        Data::Struct(data) => {
+            let fmt = display_attr(&input.attrs)?.ok_or_else(|| {
+                syn::Error::new(
+                    input.ident.span(),
+                    "missing `#[display(\"...\")]` on the struct",
+                )
+            })?;
+            validate_template(&fmt, &data.fields)?;
+            Ok(vec![DisplayArm {
+                path: quote!(Self),
+                ident: &input.ident,
+                fields: &data.fields,
+                fmt: Some(fmt),
+            }])
+        }
+        Data::Enum(data) => {
+            if let Some(attr) = input
+                .attrs
+                .iter()
+                .find(|attr| attr.path().is_ident("display"))
+            {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "on enums, `#[display(\"...\")]` goes on each variant",
+                ));
+            }
+            data.variants
+                .iter()
+                .map(|variant| {
+                    let fmt = display_attr(&variant.attrs)?;
+                    match &fmt {
+                        Some(fmt) => validate_template(fmt, &variant.fields)?,
+                        None if matches!(variant.fields, Fields::Unit) => {}
+                        None => {
+                            return Err(syn::Error::new(
+                                variant.ident.span(),
+                                "missing `#[display(\"...\")]` on this variant",
+                            ))
+                        }
+                    }
+                    let ident = &variant.ident;
+                    Ok(DisplayArm {
+                        path: quote!(Self::#ident),
+                        ident,
+                        fields: &variant.fields,
+                        fmt,
+                    })
+                })
+                .collect()
+        }
+        Data::Union(data) => Err(syn::Error::new(
+            data.union_token.span,
+            "CustomDisplay does not support unions",
+        )),
+    }
+}
+
+fn display_attr(attrs: &[Attribute]) -> syn::Result<Option<LitStr>> {
+    let mut fmt = None;
+    for attr in attrs {
+        if !attr.path().is_ident("display") {
+            continue;
+        }
+        if fmt.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "duplicate `display` attribute",
+            ));
+        }
+        match &attr.meta {
+            Meta::List(_) => fmt = Some(attr.parse_args()?),
+            meta => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "expected `#[display(\"...\")]`",
+                ))
+            }
+        }
+    }
+    Ok(fmt)
+}
+
+#[proc_macro_derive(DebugDiff, attributes(debug))]
+pub fn derive_diff(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let struct_attrs = match struct_attrs(&input.attrs, &input.data) {
+        Ok(struct_attrs) => struct_attrs,
+        Err(err) => {
+            let err = err.to_compile_error();
+            return quote! {
+                #err
+            }
+            .into();
+        }
+    };
+
+    if let Err(err) = fields(&input.data).try_for_each(|field| field_attrs(field).map(drop)) {
+        let err = err.to_compile_error();
+        return quote! {
+            #err
+        }
+        .into();
+    }
+
+    let name = &input.ident;
+    let arms = match &input.data {
+        Data::Struct(data) => vec![diff_arm(quote!(Self), &data.fields, &struct_attrs)],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                diff_arm(quote!(Self::#ident), &variant.fields, &struct_attrs)
+            })
+            .collect(),
+        Data::Union(data) => {
+            return syn::Error::new(data.union_token.span, "DebugDiff does not support unions")
+                .to_compile_error()
+                .into();
+        }
+    };
+    // Different variants are shown as a whole, which needs Self: Debug.
+    let (variant_mismatch, self_debug) = match &input.data {
+        Data::Enum(data) if data.variants.len() > 1 => (
+            Some(quote! {
+                _ => differences.push(diff_line(path, self, other)),
+            }),
+            Some(quote!(where Self: std::fmt::Debug)),
+        ),
+        _ => (None, None),
+    };
+
+    let body = if arms.is_empty() {
+        quote! {
+            match *self {}
+        }
+    } else {
+        quote! {
+            match (self, other) {
+                #(#arms)*
+                #variant_mismatch
+            }
+        }
+    };
+
+    let debug_with = debug_with_helper(&input.data);
+    let diffed = diffed_fields(&input.data, &struct_attrs);
+    let generics = add_trait_bounds(input.generics.clone(), &input.data, &struct_attrs, &diffed);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Lists the fields that differ between `self` and `other`, one
+            /// per line with their old and new values, or returns None if
+            /// there are no differences.
+            pub fn debug_diff(&self, other: &Self) -> std::option::Option<std::string::String>
+            #self_debug
+            {
+                let mut differences = std::vec::Vec::new();
+                self.__debug_diff_into(other, "", &mut differences);
+                if differences.is_empty() {
+                    std::option::Option::None
+                } else {
+                    std::option::Option::Some(differences.join("\n"))
+                }
+            }
+
+            #[doc(hidden)]
+            pub fn __debug_diff_into(
+                &self,
+                other: &Self,
+                path: &str,
+                differences: &mut std::vec::Vec<std::string::String>,
+            )
+            #self_debug
+            {
+                // Fields whose type derives DebugDiff are recursed into through
+                // their inherent `__debug_diff_into`, which method resolution
+                // prefers over this fallback comparing the Debug output.
+                trait DebugDiffFallback {
+                    fn __debug_diff_into(
+                        &self,
+                        other: &Self,
+                        path: &str,
+                        differences: &mut std::vec::Vec<std::string::String>,
+                    );
+                }
+
+                impl<T: std::fmt::Debug + ?Sized> DebugDiffFallback for T {
+                    fn __debug_diff_into(
+                        &self,
+                        other: &Self,
+                        path: &str,
+                        differences: &mut std::vec::Vec<std::string::String>,
+                    ) {
+                        if std::format!("{:?}", self) != std::format!("{:?}", other) {
+                            differences.push(diff_line(path, self, other));
+                        }
+                    }
+                }
+
+                fn diff_line<T: std::fmt::Debug + ?Sized>(
+                    path: &str,
+                    old: &T,
+                    new: &T,
+                ) -> std::string::String {
+                    if path.is_empty() {
+                        std::format!("{:?} -> {:?}", old, new)
+                    } else {
+                        std::format!("{}: {:?} -> {:?}", path, old, new)
+                    }
+                }
+
+                #debug_with
+
+                fn join(path: &str, name: &str) -> std::string::String {
+                    if path.is_empty() {
+                        std::borrow::ToOwned::to_owned(name)
+                    } else {
+                        std::format!("{}.{}", path, name)
+                    }
+                }
+
+                #body
+            }
+        }
+    }
+    .into()
+}
+
+// Compares the fields of a struct or variant pairwise. Skipped fields are
+// left out, redacted fields are reported as changed without their values, and
+// flattened fields report their own fields under the parent's path. Fields
+// with `with`, `format`, `fmt` or a preset are compared and shown as CustomDebug
+// renders them.
+fn diff_arm(path: TokenStream, fields: &Fields, struct_attrs: &StructAttrs) -> TokenStream {
+    let attrs: Vec<FieldAttrs> = fields
+        .iter()
+        .map(|field| resolved_field_attrs(field, struct_attrs))
+        .collect();
+    let fmt_members = |attrs: &FieldAttrs| match &attrs.fmt {
+        Some(fmt) => fmt.bound_args().1,
+        None => Vec::new(),
+    };
+    // Skipped fields are still bound if a compared field's `fmt` refers to them.
+    let referenced: Vec<Member> = attrs
+        .iter()
+        .filter(|attrs| !attrs.skip)
+        .flat_map(fmt_members)
+        .collect();
+    let other_binding = |member: &Member| match member {
+        Member::Named(name) => format_ident!("__other_{}", name),
+        Member::Unnamed(index) => format_ident!("__other_{}", index.index),
+    };
+    let pattern = |bindings: &[TokenStream]| match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote!(#path { #(#names: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(#path(#(#bindings),*)),
+        Fields::Unit => path.clone(),
+    };
+
+    let mut old_bindings = Vec::new();
+    let mut new_bindings = Vec::new();
+    let mut statements = Vec::new();
+    for ((i, field), attrs) in fields.iter().enumerate().zip(&attrs) {
+        let member = field_member(i, field);
+        let old = member_binding(&member);
+        let new = other_binding(&member);
+        if attrs.skip && !referenced.contains(&member) {
+            old_bindings.push(quote!(_));
+            new_bindings.push(quote!(_));
+            continue;
+        }
+        old_bindings.push(old.to_token_stream());
+        new_bindings.push(new.to_token_stream());
+        if attrs.skip {
+            continue;
+        }
+        let name = match field_name(field, attrs) {
+            Some(name) => name,
+            None => LitStr::new(&i.to_string(), Span::call_site()),
+        };
+        let rendered = attrs.with.is_some()
+            || attrs.format.is_some()
+            || attrs.fmt.is_some()
+            || attrs.preset.is_some();
+        statements.push(if attrs.redact.is_some() {
+            quote! {
+                if std::format!("{:?}", #old) != std::format!("{:?}", #new) {
+                    differences.push(diff_line(
+                        &join(path, #name),
+                        &std::format_args!("[REDACTED]"),
+                        &std::format_args!("[REDACTED]"),
+                    ));
+                }
+            }
+        } else if rendered {
+            // The same value expression renders `other` once the bindings it
+            // uses are shadowed by those of `other`.
+            let value = field_value(field, &old, attrs);
+            let mut members = Vec::new();
+            for member in match attrs.fmt {
+                Some(_) => fmt_members(attrs),
+                None => vec![member],
+            } {
+                if !members.contains(&member) {
+                    members.push(member);
+                }
+            }
+            let olds = members.iter().map(member_binding);
+            let news = members.iter().map(other_binding);
+            quote! {
+                let __old = std::format!("{:?}", #value);
+                let __new = {
+                    #(let #olds = #news;)*
+                    std::format!("{:?}", #value)
+                };
+                if __old != __new {
+                    differences.push(diff_line(
+                        &join(path, #name),
+                        &std::format_args!("{}", __old),
+                        &std::format_args!("{}", __new),
+                    ));
+                }
+            }
+        } else if attrs.flatten {
+            quote!(#old.__debug_diff_into(#new, path, differences);)
+        } else {
+            quote!(#old.__debug_diff_into(#new, &join(path, #name), differences);)
+        });
+    }
+
+    let old_pattern = pattern(&old_bindings);
+    let new_pattern = pattern(&new_bindings);
+    quote! {
+        (#old_pattern, #new_pattern) => {
+            #(#statements)*
+        }
+    }
+}
+
+// Every field that gets compared by DebugDiff, all of which need Debug.
+fn diffed_fields<'a>(data: &'a Data, struct_attrs: &StructAttrs) -> Vec<(&'a Field, &'static str)> {
+    let mut diffed = Vec::new();
+    for (siblings, field) in sibling_fields(data) {
+        let attrs = resolved_field_attrs(field, struct_attrs);
+        if attrs.skip || attrs.phantom || attrs.bound.is_some() || attrs.with.is_some() {
+            continue;
+        }
+        // Redacted fields are still compared by their Debug output.
+        if attrs.redact.is_some() {
+            diffed.push((field, "Debug"));
+        } else {
+            field_formats(siblings, field, &attrs, &mut diffed);
+        }
+    }
+    diffed
+}
+
+// Returns early from `fmt` when `this` is nested too deeply or is already being
+// formatted further up the stack, and otherwise holds guards that record it as
+// being formatted until `fmt` returns.
+fn fmt_guards(struct_attrs: &StructAttrs, this: &TokenStream) -> TokenStream {
+    let cycle_guard = struct_attrs.cycle_safe.then(|| {
+        quote! {
+            let __cycle_guard = match ::derive_debug::__private::enter_visit(#this) {
+                std::option::Option::Some(guard) => guard,
+                std::option::Option::None => return f.write_str("<cycle>"),
+            };
+        }
+    });
+    let depth_guard = struct_attrs.max_depth.map(|max_depth| {
+        quote! {
+            let __depth_guard = match ::derive_debug::__private::enter_depth(#max_depth) {
+                std::option::Option::Some(guard) => guard,
+                std::option::Option::None => return f.write_str("…"),
+            };
+        }
+    });
+    quote! {
+        #cycle_guard
+        #depth_guard
+    }
+}
+
+// The body of `fmt`, formatting `this`, whose type is named by `this_path` in
+// patterns and displayed as `type_name`.
+fn debug_body(
+    data: &Data,
+    struct_attrs: &StructAttrs,
+    this: TokenStream,
+    this_path: TokenStream,
+    type_name: &Ident,
+) -> TokenStream {
+    let guards = fmt_guards(struct_attrs, &this);
+    let body = match (data, &struct_attrs.fmt) {
+        (Data::Struct(data), _) if struct_attrs.transparent => {
+            let arm = transparent_arm(this_path, &data.fields, struct_attrs);
+            quote! {
+                match #this {
+                    #arm
+                }
+            }
+        }
+        (Data::Struct(_), Some(fmt)) => {
+            let arm = template_arm(this_path, fmt);
+            quote! {
+                match #this {
+                    #arm
+                }
+            }
+        }
+        (Data::Struct(data), None) => {
+            let lit_name = struct_attrs
+                .rename
+                .clone()
+                .unwrap_or_else(|| LitStr::new(&type_name.unraw().to_string(), type_name.span()));
+            let arm = variant_debug(this_path, &lit_name, &data.fields, struct_attrs);
+            quote! {
+                match #this {
+                    #arm
+                }
+            }
+        }
+        (Data::Enum(data), _) if data.variants.is_empty() => quote! {
+            match *#this {}
+        },
+        (Data::Enum(data), _) => {
+            let arms = data.variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let lit_name = variant_attrs(variant)
+                    .ok()
+                    .and_then(|attrs| attrs.rename)
+                    .unwrap_or_else(|| LitStr::new(&ident.unraw().to_string(), ident.span()));
+                variant_debug(
+                    quote!(#this_path::#ident),
+                    &lit_name,
+                    &variant.fields,
+                    struct_attrs,
+                )
+            });
+            quote! {
+                match #this {
+                    #(#arms)*
+                }
+            }
+        }
+        (Data::Union(_), _) => unreachable!("rejected by derive"),
+    };
+    quote! {
+        #guards
+        #body
+    }
+}
+
+struct DebugBound {
+    generic: Option<Ident>,
+    predicate: WherePredicate,
+}
+
+impl DebugBound {
+    // A predicate on an associated type such as `T::Value: Debug` takes the
+    // place of the `T: Debug` bound that would otherwise be inferred.
+    fn new(predicate: WherePredicate) -> Self {
+        let generic = match &predicate {
+            WherePredicate::Type(PredicateType {
+                bounded_ty: Type::Path(TypePath { path, .. }),
+                ..
+            }) if path.segments.len() > 1 => Some(path.segments[0].ident.clone()),
+            _ => None,
+        };
+        DebugBound { generic, predicate }
+    }
+}
+
+fn parse_bounds(lit: &LitStr) -> syn::Result<Vec<WherePredicate>> {
+    let predicates = lit.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+    Ok(predicates.into_iter().collect())
+}
+
+#[derive(Default)]
+struct StructAttrs {
+    bounds: Vec<DebugBound>,
+    skip_inference: bool,
+    redact_all_except: Option<Vec<Ident>>,
+    fmt: Option<LitStr>,
+    rename: Option<LitStr>,
+    rename_all: Option<RenameRule>,
+    transparent: bool,
+    remote: Option<Path>,
+    sort_fields: bool,
+    visit: bool,
+    max_depth: Option<usize>,
+    cycle_safe: bool,
+}
+
+const STRUCT_CONFLICTS: &[(&str, &str)] = &[
+    ("transparent", "fmt"),
+    ("transparent", "rename"),
+    ("transparent", "rename_all"),
+    ("fmt", "redact_all_except"),
+];
+
+fn struct_attrs(attrs: &[Attribute], data: &Data) -> syn::Result<StructAttrs> {
+    let mut struct_attrs = StructAttrs::default();
+    let mut keys = Keys::new(STRUCT_CONFLICTS);
+    for attr in attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        if let Meta::NameValue(meta) = &attr.meta {
+            return Err(syn::Error::new_spanned(
+                meta,
+                "expected `#[debug(...)]` on the type",
+            ));
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bound") {
+                keys.insert_repeatable(&meta.path)?;
+                let lit = meta.value()?.parse::<LitStr>()?;
+                let predicates = parse_bounds(&lit)?;
+                if predicates.is_empty() {
+                    struct_attrs.skip_inference = true;
+                }
+                struct_attrs
+                    .bounds
+                    .extend(predicates.into_iter().map(DebugBound::new));
+            } else if meta.path.is_ident("redact_all_except") {
+                keys.insert(&meta.path)?;
+                let content;
+                parenthesized!(content in meta.input);
+                let names = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?;
+                for name in &names {
+                    if !fields(data).any(|field| field.ident.as_ref() == Some(name)) {
+                        return Err(syn::Error::new(name.span(), "no field with this name"));
+                    }
+                }
+                struct_attrs.redact_all_except = Some(names.into_iter().collect());
+            } else if meta.path.is_ident("fmt") {
+                keys.insert(&meta.path)?;
+                let fmt = meta.value()?.parse()?;
+                validate_struct_fmt(&fmt, data)?;
+                struct_attrs.fmt = Some(fmt);
+            } else if meta.path.is_ident("rename") {
+                keys.insert(&meta.path)?;
+                struct_attrs.rename = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("rename_all") {
+                keys.insert(&meta.path)?;
+                let lit = meta.value()?.parse::<LitStr>()?;
+                struct_attrs.rename_all = Some(RenameRule::from_lit(&lit)?);
+            } else if meta.path.is_ident("max_depth") {
+                keys.insert(&meta.path)?;
+                let max_depth = meta.value()?.parse::<LitInt>()?;
+                if max_depth.base10_parse::<usize>()? == 0 {
+                    return Err(syn::Error::new(
+                        max_depth.span(),
+                        "`max_depth` must be at least 1",
+                    ));
+                }
+                struct_attrs.max_depth = Some(max_depth.base10_parse()?);
+            } else if meta.path.is_ident("cycle_safe") {
+                keys.insert(&meta.path)?;
+                struct_attrs.cycle_safe = true;
+            } else if meta.path.is_ident("visit") {
+                keys.insert(&meta.path)?;
+                struct_attrs.visit = true;
+            } else if meta.path.is_ident("sort_fields") {
+                keys.insert(&meta.path)?;
+                // Flattened fields are written by the nested type, in its own
+                // order, so they cannot be sorted among the others.
+                if fields(data).any(|field| field_attrs(field).is_ok_and(|attrs| attrs.flatten)) {
+                    return Err(
+                        meta.error("`sort_fields` cannot be combined with `flatten` fields")
+                    );
+                }
+                struct_attrs.sort_fields = true;
+            } else if meta.path.is_ident("remote") {
+                keys.insert(&meta.path)?;
+                struct_attrs.remote = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+            } else if meta.path.is_ident("transparent") {
+                keys.insert(&meta.path)?;
+                validate_transparent(&meta.path, data)?;
+                struct_attrs.transparent = true;
+            } else {
+                return Err(unknown_key(&meta.path));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(struct_attrs)
+}
+
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    const ALL: &'static [(&'static str, RenameRule)] = &[
+        ("lowercase", RenameRule::Lower),
+        ("UPPERCASE", RenameRule::Upper),
+        ("PascalCase", RenameRule::Pascal),
+        ("camelCase", RenameRule::Camel),
+        ("snake_case", RenameRule::Snake),
+        ("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnake),
+        ("kebab-case", RenameRule::Kebab),
+        ("SCREAMING-KEBAB-CASE", RenameRule::ScreamingKebab),
+    ];
+
+    fn from_lit(lit: &LitStr) -> syn::Result<Self> {
+        let value = lit.value();
+        match RenameRule::ALL.iter().find(|(name, _)| *name == value) {
+            Some((_, rule)) => Ok(*rule),
+            None => {
+                let names: Vec<String> = RenameRule::ALL
+                    .iter()
+                    .map(|(name, _)| format!("\"{}\"", name))
+                    .collect();
+                Err(syn::Error::new(
+                    lit.span(),
+                    format!("unknown rename rule, expected one of {}", names.join(", ")),
+                ))
+            }
+        }
+    }
+
+    // Field names are snake_case, so words are split on underscores.
+    fn apply(self, name: &str) -> String {
+        let words = name.split('_').filter(|word| !word.is_empty());
+        let capitalize = |word: &str| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect::<String>()
+            })
+        };
+        match self {
+            RenameRule::Lower => name.to_lowercase(),
+            RenameRule::Upper => name.to_uppercase(),
+            RenameRule::Pascal => words.map(capitalize).collect(),
+            RenameRule::Camel => {
+                let pascal: String = words.map(capitalize).collect();
+                let mut chars = pascal.chars();
+                chars.next().map_or_else(String::new, |first| {
+                    first.to_lowercase().chain(chars).collect()
+                })
+            }
+            RenameRule::Snake => name.to_owned(),
+            RenameRule::ScreamingSnake => name.to_uppercase(),
+            RenameRule::Kebab => name.replace('_', "-"),
+            RenameRule::ScreamingKebab => name.replace('_', "-").to_uppercase(),
+        }
+    }
+}
+
+fn add_trait_bounds(
+    mut generics: Generics,
+    data: &Data,
+    struct_attrs: &StructAttrs,
+    formatted: &[(&Field, &'static str)],
+) -> Generics {
+    let bounds = &struct_attrs.bounds;
+    let where_clause = generics.make_where_clause();
+    for DebugBound { predicate, .. } in bounds {
+        where_clause.predicates.push(predicate.clone());
+    }
+    for field in fields(data) {
+        if let Some(field_bounds) = field_attrs(field).ok().and_then(|attrs| attrs.bound) {
+            where_clause.predicates.extend(field_bounds);
+        }
+    }
+    if !struct_attrs.skip_inference {
+        infer_bounds(&mut generics, formatted, bounds);
+    }
+    generics
+}
+
+// Bounds each type parameter and associated type that appears in a formatted
+// field with the std::fmt trait the field is formatted with, except for
+// parameters already covered by an explicit associated type bound.
+fn infer_bounds(
+    generics: &mut Generics,
+    formatted: &[(&Field, &'static str)],
+    explicit: &[DebugBound],
+) {
+    let type_params: Vec<Ident> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let mut params: Vec<(Ident, Ident)> = Vec::new();
+    let mut associated_types: Vec<(Type, Ident)> = Vec::new();
+    for (field, fmt_trait) in formatted {
+        let fmt_trait = Ident::new(fmt_trait, Span::call_site());
+        let mut visitor = BoundVisitor {
+            type_params: &type_params,
+            params: Vec::new(),
+            associated_types: Vec::new(),
+        };
+        visitor.visit_type(&field.ty);
+        for param in visitor.params {
+            let explicit = explicit
+                .iter()
+                .any(|DebugBound { generic, .. }| generic.as_ref() == Some(&param));
+            let bound = (param, fmt_trait.clone());
+            if !explicit && !params.contains(&bound) {
+                params.push(bound);
+            }
+        }
+        for ty in visitor.associated_types {
+            let bound = (ty, fmt_trait.clone());
+            if !associated_types.contains(&bound) {
+                associated_types.push(bound);
+            }
+        }
+    }
+
+    for param in &mut generics.params {
+        match param {
+            GenericParam::Type(type_param) => {
+                for (ident, fmt_trait) in &params {
+                    if *ident == type_param.ident {
+                        type_param.bounds.push(parse_quote!(std::fmt::#fmt_trait));
+                    }
+                }
+            }
+            // Lifetimes and const parameters never need a formatting bound,
+            // `[T; N]` only constrains its element type.
+            GenericParam::Lifetime(_) | GenericParam::Const(_) => {}
+        }
+    }
+    let where_clause = generics.make_where_clause();
+    for (ty, fmt_trait) in associated_types {
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: std::fmt::#fmt_trait));
+    }
+}
+
+// Collects the type parameters and associated types that a field's type needs
+// to be formattable. Types which implement the std::fmt traits regardless of
+// their parameters, like PhantomData, raw pointers and function pointers, are
+// not looked into.
+struct BoundVisitor<'a> {
+    type_params: &'a [Ident],
+    params: Vec<Ident>,
+    associated_types: Vec<Type>,
+}
+
+impl<'ast> Visit<'ast> for BoundVisitor<'_> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        if let Some(qself) = &ty.qself {
+            let mut visitor = BoundVisitor {
+                type_params: self.type_params,
+                params: Vec::new(),
+                associated_types: Vec::new(),
+            };
+            visitor.visit_type(&qself.ty);
+            if !visitor.params.is_empty() {
+                self.associated_types.push(Type::Path(ty.clone()));
+                return;
+            }
+        } else if ty.path.leading_colon.is_none() {
+            let first = &ty.path.segments[0].ident;
+            if self.type_params.contains(first) {
+                if ty.path.segments.len() == 1 {
+                    self.params.push(first.clone());
+                } else {
+                    self.associated_types.push(Type::Path(ty.clone()));
+                }
+                return;
+            }
+        }
+        if ty
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "PhantomData")
+        {
+            return;
+        }
+        visit::visit_type_path(self, ty);
+    }
+
+    fn visit_type_ptr(&mut self, _: &'ast TypePtr) {}
+
+    fn visit_type_bare_fn(&mut self, _: &'ast TypeBareFn) {}
+
+    fn visit_type_trait_object(&mut self, _: &'ast TypeTraitObject) {}
+
+    fn visit_type_impl_trait(&mut self, _: &'ast TypeImplTrait) {}
+}
+
+fn fields(data: &Data) -> Box<dyn Iterator<Item = &Field> + '_> {
+    match data {
+        Data::Struct(data) => Box::new(data.fields.iter()),
+        Data::Enum(data) => Box::new(data.variants.iter().flat_map(|variant| &variant.fields)),
+        Data::Union(data) => Box::new(data.fields.named.iter()),
+    }
+}
+
+// Every field that gets formatted, paired with the std::fmt trait it is
+// formatted with.
+fn formatted_fields<'a>(
+    data: &'a Data,
+    struct_attrs: &StructAttrs,
+) -> Vec<(&'a Field, &'static str)> {
+    if let (Some(fmt), Data::Struct(data)) = (&struct_attrs.fmt, data) {
+        return template_fields(fmt, &data.fields)
+            .into_iter()
+            .filter(|(field, _)| !field_attrs(field).is_ok_and(|attrs| attrs.phantom))
+            .collect();
+    }
+
+    let mut formatted = Vec::new();
+    for (siblings, field) in sibling_fields(data) {
+        let attrs = resolved_field_attrs(field, struct_attrs);
+        if attrs.skip
+            || attrs.redact.is_some()
+            || attrs.with.is_some()
+            || attrs.bound.is_some()
+            || attrs.phantom
+        {
+            continue;
+        }
+        field_formats(siblings, field, &attrs, &mut formatted);
+    }
+    formatted
+}
+
+// Every field along with the fields of the same struct or variant, which
+// `self.<member>` in its `fmt` arguments refers to.
+fn sibling_fields(data: &Data) -> impl Iterator<Item = (&Fields, &Field)> {
+    let field_lists: Vec<&Fields> = match data {
+        Data::Struct(data) => vec![&data.fields],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| &variant.fields)
+            .collect(),
+        Data::Union(_) => Vec::new(),
+    };
+    field_lists
+        .into_iter()
+        .flat_map(|siblings| siblings.iter().map(move |field| (siblings, field)))
+}
+
+// The fields that formatting `field` as its attributes say formats, paired
+// with the std::fmt trait each is formatted with.
+fn field_formats<'a>(
+    siblings: &'a Fields,
+    field: &'a Field,
+    attrs: &FieldAttrs,
+    formatted: &mut Vec<(&'a Field, &'static str)>,
+) {
+    if let Some(format) = &attrs.format {
+        for placeholder in parse_format(format).unwrap_or_default() {
+            formatted.push((field, placeholder.fmt_trait));
+        }
+    } else if attrs.flatten {
+        formatted.push((field, "Debug"));
+    } else if let Some(preset) = attrs.preset {
+        match preset {
+            Preset::Hex => formatted.push((field, "LowerHex")),
+            Preset::Bin => formatted.push((field, "Binary")),
+            Preset::Bytes => {}
+            Preset::Truncate(_) => formatted.push((field, "Debug")),
+        }
+    } else if let Some(FieldFmt { format, args }) = &attrs.fmt {
+        for placeholder in parse_format(format).unwrap_or_default() {
+            let FormatArg::Index(index) = placeholder.arg else {
+                continue;
+            };
+            let referenced =
+                self_member(&args[index]).and_then(|member| field_by_member(siblings, member));
+            if let Some(field) = referenced {
+                formatted.push((field, placeholder.fmt_trait));
+            }
+        }
+    } else {
+        formatted.push((field, "Debug"));
+    }
+}
+
+// The fields interpolated by a format string written against a struct or
+// variant, paired with the std::fmt trait each one is formatted with.
+fn template_fields<'a>(fmt: &LitStr, fields: &'a Fields) -> Vec<(&'a Field, &'static str)> {
+    parse_format(fmt)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|placeholder| {
+            let field = field_by_member(fields, &placeholder.arg.member(fmt.span()))?;
+            Some((field, placeholder.fmt_trait))
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    format: Option<LitStr>,
+    skip: bool,
+    skip_if: Option<Path>,
+    redact: Option<Redact>,
+    with: Option<Path>,
+    rename: Option<LitStr>,
+    fmt: Option<FieldFmt>,
+    bound: Option<Vec<WherePredicate>>,
+    preset: Option<Preset>,
+    order: Option<i64>,
+    flatten: bool,
+    phantom: bool,
+}
+
+struct FieldFmt {
+    format: LitStr,
+    args: Vec<Expr>,
+}
+
+impl FieldFmt {
+    // The arguments are written against `self`, but evaluated inside a match on
+    // the fields, where `self` may be an enum or, with `remote`, a wrapper.
+    // Every `self.<member>` is replaced by the member's binding, and the
+    // members are returned so that the match binds them.
+    fn bound_args(&self) -> (Vec<Expr>, Vec<Member>) {
+        let mut rewriter = SelfMembers(Vec::new());
+        let mut args = self.args.clone();
+        for arg in &mut args {
+            rewriter.visit_expr_mut(arg);
+        }
+        (args, rewriter.0)
+    }
+}
+
+struct SelfMembers(Vec<Member>);
+
+impl VisitMut for SelfMembers {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match self_member(expr) {
+            Some(member) => {
+                let binding = member_binding(member);
+                self.0.push(member.clone());
+                *expr = parse_quote!(#binding);
+            }
+            None => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Redact {
+    Full,
+    Partial,
+}
+
+#[derive(Clone, Copy)]
+enum Preset {
+    Hex,
+    Bin,
+    Bytes,
+    Truncate(usize),
+}
+
+const FIELD_CONFLICTS: &[(&str, &str)] = &[
+    ("format", "with"),
+    ("format", "redact"),
+    ("format", "fmt"),
+    ("with", "redact"),
+    ("with", "fmt"),
+    ("redact", "fmt"),
+    ("skip", "format"),
+    ("skip", "fmt"),
+    ("skip", "with"),
+    ("skip", "redact"),
+    ("skip", "skip_if"),
+    ("skip", "rename"),
+    ("skip", "bound"),
+    ("hex", "bin"),
+    ("hex", "bytes"),
+    ("hex", "truncate"),
+    ("bin", "bytes"),
+    ("bin", "truncate"),
+    ("bytes", "truncate"),
+    ("hex", "format"),
+    ("hex", "with"),
+    ("hex", "redact"),
+    ("hex", "fmt"),
+    ("hex", "skip"),
+    ("bin", "format"),
+    ("bin", "with"),
+    ("bin", "redact"),
+    ("bin", "fmt"),
+    ("bin", "skip"),
+    ("bytes", "format"),
+    ("bytes", "with"),
+    ("bytes", "redact"),
+    ("bytes", "fmt"),
+    ("bytes", "skip"),
+    ("truncate", "format"),
+    ("truncate", "with"),
+    ("truncate", "redact"),
+    ("truncate", "fmt"),
+    ("truncate", "skip"),
+    ("flatten", "format"),
+    ("flatten", "with"),
+    ("flatten", "redact"),
+    ("flatten", "fmt"),
+    ("flatten", "skip"),
+    ("flatten", "skip_if"),
+    ("flatten", "rename"),
+    ("flatten", "hex"),
+    ("flatten", "bin"),
+    ("flatten", "bytes"),
+    ("flatten", "truncate"),
+    ("skip", "order"),
+    ("skip", "phantom"),
+    ("phantom", "bound"),
+];
+
+struct Keys {
+    seen: Vec<String>,
+    conflicts: &'static [(&'static str, &'static str)],
+}
+
+impl Keys {
+    fn new(conflicts: &'static [(&'static str, &'static str)]) -> Self {
+        Keys {
+            seen: Vec::new(),
+            conflicts,
+        }
+    }
+
+    fn insert(&mut self, path: &Path) -> syn::Result<()> {
+        let key = path.to_token_stream().to_string();
+        if self.seen.contains(&key) {
+            return Err(syn::Error::new_spanned(
+                path,
+                format!("duplicate debug attribute `{}`", key),
+            ));
+        }
+        for (a, b) in self.conflicts {
+            let other = match key.as_str() {
+                k if k == *a => b,
+                k if k == *b => a,
+                _ => continue,
+            };
+            if self.seen.iter().any(|seen| seen == other) {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    format!("`{}` conflicts with `{}`", key, other),
+                ));
+            }
+        }
+        self.seen.push(key);
+        Ok(())
+    }
+
+    // Like `insert`, for keys that may be given more than once.
+    fn insert_repeatable(&mut self, path: &Path) -> syn::Result<()> {
+        let key = path.to_token_stream().to_string();
+        if self.seen.contains(&key) {
+            return Ok(());
+        }
+        self.insert(path)
+    }
+}
+
+fn unknown_key(path: &Path) -> syn::Error {
+    syn::Error::new_spanned(
+        path,
+        format!("unknown debug attribute `{}`", path.to_token_stream()),
+    )
+}
+
+fn field_attrs(field: &Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs::default();
+    let mut keys = Keys::new(FIELD_CONFLICTS);
+    for attr in &field.attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        if let Meta::NameValue(meta) = &attr.meta {
+            keys.insert(&Ident::new("format", meta.path.span()).into())?;
+            let format = syn::parse2(meta.value.to_token_stream())?;
+            validate_field_format(&format)?;
+            attrs.format = Some(format);
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                keys.insert(&meta.path)?;
+                let format = meta.value()?.parse()?;
+                validate_field_format(&format)?;
+                attrs.format = Some(format);
+            } else if meta.path.is_ident("skip") {
+                keys.insert(&meta.path)?;
+                attrs.skip = true;
+            } else if meta.path.is_ident("skip_if") {
+                keys.insert(&meta.path)?;
+                attrs.skip_if = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+            } else if meta.path.is_ident("redact") {
+                keys.insert(&meta.path)?;
+                attrs.redact = Some(Redact::Full);
+                if meta.input.peek(Token![=]) {
+                    let lit = meta.value()?.parse::<LitStr>()?;
+                    if lit.value() != "partial" {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            "expected `redact = \"partial\"`",
+                        ));
+                    }
+                    attrs.redact = Some(Redact::Partial);
+                }
+            } else if meta.path.is_ident("with") {
+                keys.insert(&meta.path)?;
+                let value = meta.value()?;
+                attrs.with = Some(if value.peek(LitStr) {
+                    value.parse::<LitStr>()?.parse()?
+                } else {
+                    value.parse()?
+                });
+            } else if meta.path.is_ident("rename") {
+                keys.insert(&meta.path)?;
+                if field.ident.is_none() {
+                    return Err(meta.error("`rename` is not supported on tuple fields"));
+                }
+                attrs.rename = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("bound") {
+                keys.insert_repeatable(&meta.path)?;
+                let lit = meta.value()?.parse::<LitStr>()?;
+                attrs
+                    .bound
+                    .get_or_insert_with(Vec::new)
+                    .extend(parse_bounds(&lit)?);
+            } else if meta.path.is_ident("fmt") {
+                keys.insert(&meta.path)?;
+                let format = meta.value()?.parse()?;
+                let mut args = Vec::new();
+                while !meta.input.is_empty() {
+                    meta.input.parse::<Token![,]>()?;
+                    if meta.input.is_empty() {
+                        break;
+                    }
+                    // Named arguments are not supported, so `key = ...` here is
+                    // another key that was meant to come before `fmt`.
+                    if meta.input.peek(Ident)
+                        && meta.input.peek2(Token![=])
+                        && !meta.input.peek2(Token![==])
+                    {
+                        return Err(syn::Error::new(
+                            meta.input.span(),
+                            "`fmt` must be the last key in #[debug(...)]",
+                        ));
+                    }
+                    args.push(meta.input.parse()?);
+                }
+                validate_fmt_args(&format, &args)?;
+                attrs.fmt = Some(FieldFmt { format, args });
+            } else if meta.path.is_ident("order") {
+                keys.insert(&meta.path)?;
+                attrs.order = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+            } else if meta.path.is_ident("phantom") {
+                keys.insert(&meta.path)?;
+                attrs.phantom = true;
+            } else if meta.path.is_ident("flatten") {
+                keys.insert(&meta.path)?;
+                if field.ident.is_none() {
+                    return Err(meta.error("`flatten` is not supported on tuple fields"));
+                }
+                attrs.flatten = true;
+            } else if meta.path.is_ident("hex") {
+                keys.insert(&meta.path)?;
+                attrs.preset = Some(Preset::Hex);
+            } else if meta.path.is_ident("bin") {
+                keys.insert(&meta.path)?;
+                attrs.preset = Some(Preset::Bin);
+            } else if meta.path.is_ident("bytes") {
+                keys.insert(&meta.path)?;
+                attrs.preset = Some(Preset::Bytes);
+            } else if meta.path.is_ident("truncate") {
+                keys.insert(&meta.path)?;
+                let len = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                attrs.preset = Some(Preset::Truncate(len));
+            } else {
+                return Err(unknown_key(&meta.path));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(attrs)
+}
+
+#[derive(Clone, PartialEq)]
+enum FormatArg {
+    Index(usize),
+    Name(String),
+}
+
+impl FormatArg {
+    fn member(&self, span: Span) -> Member {
+        match self {
+            FormatArg::Index(index) => Member::Unnamed(syn::Index {
+                index: *index as u32,
+                span,
+            }),
+            FormatArg::Name(name) => Member::Named(Ident::new(name, span)),
+        }
+    }
+}
+
+struct Placeholder {
+    arg: FormatArg,
+    // Byte range of the argument within the string, empty for an implicit `{}`.
+    arg_range: Range<usize>,
+    fmt_trait: &'static str,
+    // Width and precision parameters taken from other arguments.
+    counts: Vec<FormatArg>,
+    span: Span,
+}
+
+fn validate_field_format(format: &LitStr) -> syn::Result<()> {
+    let placeholders = parse_format(format)?;
+    if placeholders.is_empty() {
+        return Err(syn::Error::new(
+            format.span(),
+            "format string must contain a `{}` placeholder for the field",
+        ));
+    }
+    for placeholder in placeholders {
+        for arg in placeholder.counts.iter().chain([&placeholder.arg]) {
+            match arg {
+                FormatArg::Index(0) => {}
+                FormatArg::Index(_) => {
+                    return Err(syn::Error::new(
+                        placeholder.span,
+                        "format string for a field takes exactly one argument",
+                    ))
+                }
+                FormatArg::Name(name) => {
+                    return Err(syn::Error::new(
+                        placeholder.span,
+                        format!("named argument `{}` is not available here", name),
+                    ))
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_fmt_args(format: &LitStr, args: &[Expr]) -> syn::Result<()> {
+    let mut used = vec![false; args.len()];
+    for placeholder in parse_format(format)? {
+        for arg in placeholder.counts.iter().chain([&placeholder.arg]) {
+            match arg {
+                FormatArg::Index(index) if *index < args.len() => used[*index] = true,
+                FormatArg::Index(index) => {
+                    return Err(syn::Error::new(
+                        placeholder.span,
+                        format!(
+                            "invalid reference to positional argument {} ({} given)",
+                            index,
+                            args.len(),
+                        ),
+                    ))
+                }
+                FormatArg::Name(name) => {
+                    return Err(syn::Error::new(
+                        placeholder.span,
+                        format!("named argument `{}` is not available here", name),
+                    ))
+                }
+            }
+        }
+    }
+    match used.iter().position(|used| !used) {
+        Some(unused) => Err(syn::Error::new_spanned(
+            &args[unused],
+            "argument never used in format string",
+        )),
+        None => Ok(()),
+    }
+}
+
+fn parse_format(format: &LitStr) -> syn::Result<Vec<Placeholder>> {
+    let value = format.value();
+    let mut placeholders = Vec::new();
+    let mut next = 0;
+    let mut next_arg = || {
+        next += 1;
+        FormatArg::Index(next - 1)
+    };
+    let mut i = 0;
+    while let Some(c) = value[i..].chars().next() {
+        let rest = &value[i + c.len_utf8()..];
+        match c {
+            '{' if rest.starts_with('{') => i += 2,
+            '}' if rest.starts_with('}') => i += 2,
+            '}' => {
+                let span = format_span(format, i..i + 1);
+                return Err(syn::Error::new(span, "unmatched `}` in format string"));
+            }
+            '{' => {
+                let end = match rest.find('}') {
+                    Some(end) => i + 1 + end,
+                    None => {
+                        let span = format_span(format, i..value.len());
+                        return Err(syn::Error::new(span, "unmatched `{` in format string"));
+                    }
+                };
+                let span = format_span(format, i..end + 1);
+                let (arg, spec) = value[i + 1..end]
+                    .split_once(':')
+                    .unwrap_or((&value[i + 1..end], ""));
+                let arg_range = i + 1..i + 1 + arg.len();
+                let spec = parse_format_spec(spec).map_err(|msg| syn::Error::new(span, msg))?;
+                let counts = spec
+                    .counts
+                    .into_iter()
+                    .map(|count| count.unwrap_or_else(&mut next_arg))
+                    .collect();
+                let arg = if arg.is_empty() {
+                    next_arg()
+                } else if let Ok(index) = arg.parse() {
+                    FormatArg::Index(index)
+                } else if is_identifier(arg) {
+                    FormatArg::Name(arg.to_owned())
+                } else {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("invalid argument `{}` in format string", arg),
+                    ));
+                };
+                placeholders.push(Placeholder {
+                    arg,
+                    arg_range,
+                    fmt_trait: spec.fmt_trait,
+                    counts,
+                    span,
+                });
+                i = end + 1;
+            }
+            _ => i += c.len_utf8(),
+        }
+    }
+    Ok(placeholders)
+}
+
+struct FormatSpec {
+    // Width and precision parameters in the order they are consumed. `None`
+    // is a `.*` precision, which takes the next positional argument.
+    counts: Vec<Option<FormatArg>>,
+    fmt_trait: &'static str,
+}
+
+fn parse_format_spec(spec: &str) -> Result<FormatSpec, String> {
+    let mut counts = Vec::new();
+    let mut rest = spec;
+
+    let mut chars = rest.chars();
+    match (chars.next(), chars.next()) {
+        (Some(fill), Some('<' | '^' | '>')) => rest = &rest[fill.len_utf8() + 1..],
+        (Some('<' | '^' | '>'), _) => rest = &rest[1..],
+        _ => {}
+    }
+    rest = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+    rest = rest.strip_prefix('#').unwrap_or(rest);
+    if !rest.starts_with("0$") {
+        rest = rest.strip_prefix('0').unwrap_or(rest);
+    }
+    if let Some((count, after)) = parse_count(rest) {
+        counts.extend(count.map(Some));
+        rest = after;
+    }
+    if let Some(after) = rest.strip_prefix('.') {
+        if let Some(after) = after.strip_prefix('*') {
+            counts.push(None);
+            rest = after;
+        } else if let Some((count, after)) = parse_count(after) {
+            counts.extend(count.map(Some));
+            rest = after;
+        } else {
+            return Err("expected precision after `.` in format spec".to_owned());
+        }
+    }
+
+    let fmt_trait = match rest {
+        "" => "Display",
+        "?" | "x?" | "X?" => "Debug",
+        "x" => "LowerHex",
+        "X" => "UpperHex",
+        "o" => "Octal",
+        "b" => "Binary",
+        "e" => "LowerExp",
+        "E" => "UpperExp",
+        "p" => "Pointer",
+        _ => return Err(format!("unknown format trait `{}`", rest)),
+    };
+    Ok(FormatSpec { counts, fmt_trait })
+}
+
+// Parses an integer width or precision, or an `index$` / `name$` parameter
+// referring to another argument.
+fn parse_count(spec: &str) -> Option<(Option<FormatArg>, &str)> {
+    let digits = spec
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(spec.len());
+    if digits > 0 {
+        let (count, rest) = spec.split_at(digits);
+        return Some(match rest.strip_prefix('$') {
+            Some(rest) => (Some(FormatArg::Index(count.parse().ok()?)), rest),
+            None => (None, rest),
+        });
+    }
+    let len = spec
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(spec.len());
+    let (name, rest) = spec.split_at(len);
+    let rest = rest.strip_prefix('$')?;
+    is_identifier(name).then(|| (Some(FormatArg::Name(name.to_owned())), rest))
+}
+
+fn is_identifier(s: &str) -> bool {
+    syn::parse_str::<Ident>(s).is_ok()
+}
+
+// Points at a byte range of the string's value. Subspans are only resolved on
+// nightly and only when the literal has no escapes, otherwise this falls back
+// to the span of the whole literal.
+fn format_span(format: &LitStr, range: Range<usize>) -> Span {
+    let token = format.token();
+    let repr = token.to_string();
+    let value = format.value();
+    let start = repr.find('"').map_or(0, |quote| quote + 1);
+    if repr.get(start..start + value.len()) != Some(value.as_str()) {
+        return format.span();
+    }
+    token
+        .subspan(start + range.start..start + range.end)
+        .unwrap_or_else(|| format.span())
+}
+
+fn validate_struct_fmt(fmt: &LitStr, data: &Data) -> syn::Result<()> {
+    let fields = match data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                fmt.span(),
+                "`fmt` on the type is only supported for structs",
+            ))
+        }
+    };
+    validate_template(fmt, fields)?;
+
+    // The template formats fields directly, so attributes that would hide or
+    // reformat an interpolated field cannot take effect.
+    for placeholder in parse_format(fmt)? {
+        let member = placeholder.arg.member(placeholder.span);
+        let Some(attrs) =
+            field_by_member(fields, &member).and_then(|field| field_attrs(field).ok())
+        else {
+            continue;
+        };
+        let ignored = if attrs.skip {
+            "skip"
+        } else if attrs.skip_if.is_some() {
+            "skip_if"
+        } else if attrs.redact.is_some() {
+            "redact"
+        } else if attrs.with.is_some() {
+            "with"
+        } else if attrs.format.is_some() {
+            "format"
+        } else if attrs.fmt.is_some() {
+            "fmt"
+        } else {
+            match attrs.preset {
+                Some(Preset::Hex) => "hex",
+                Some(Preset::Bin) => "bin",
+                Some(Preset::Bytes) => "bytes",
+                Some(Preset::Truncate(_)) => "truncate",
+                None => continue,
+            }
+        };
+        return Err(syn::Error::new(
+            placeholder.span,
+            format!(
+                "field `{}` has `#[debug({})]`, which the type-level `fmt` cannot apply",
+                member.to_token_stream(),
+                ignored,
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_template(fmt: &LitStr, fields: &Fields) -> syn::Result<()> {
+    for placeholder in parse_format(fmt)? {
+        if !placeholder.counts.is_empty() {
+            return Err(syn::Error::new(
+                placeholder.span,
+                "width and precision parameters are not supported here",
+            ));
+        }
+        let member = placeholder.arg.member(placeholder.span);
+        if field_by_member(fields, &member).is_none() {
+            return Err(syn::Error::new(
+                placeholder.span,
+                format!("no field `{}`", member.to_token_stream()),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_transparent(path: &Path, data: &Data) -> syn::Result<()> {
+    let Data::Struct(data) = data else {
+        return Err(syn::Error::new_spanned(
+            path,
+            "`transparent` is only supported for structs",
+        ));
+    };
+    let shown: Vec<&Field> = data
+        .fields
+        .iter()
+        .filter(|field| !field_attrs(field).is_ok_and(|attrs| attrs.skip))
+        .collect();
+    if shown.len() != 1 {
+        return Err(syn::Error::new_spanned(
+            path,
+            format!(
+                "`transparent` requires exactly one non-skipped field, found {}",
+                shown.len(),
+            ),
+        ));
+    }
+    if let Some(skip_if) = field_attrs(shown[0]).ok().and_then(|attrs| attrs.skip_if) {
+        return Err(syn::Error::new_spanned(
+            skip_if,
+            "`skip_if` is not supported on the field of a `transparent` struct",
+        ));
+    }
+    Ok(())
+}
+
+fn field_by_member<'a>(fields: &'a Fields, member: &Member) -> Option<&'a Field> {
+    match member {
+        Member::Named(name) => fields
+            .iter()
+            .find(|field| field.ident.as_ref() == Some(name)),
+        Member::Unnamed(index) => match fields {
+            Fields::Unnamed(_) => fields.iter().nth(index.index as usize),
+            _ => None,
+        },
+    }
+}
+
+// Recognizes a format argument of the form `self.field`.
+fn self_member(expr: &Expr) -> Option<&Member> {
+    match expr {
+        Expr::Field(ExprField { base, member, .. }) => match &**base {
+            Expr::Path(ExprPath { path, .. }) if path.is_ident("self") => Some(member),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct VariantAttrs {
+    rename: Option<LitStr>,
+}
+
+fn variant_attrs(variant: &Variant) -> syn::Result<VariantAttrs> {
+    let mut attrs = VariantAttrs::default();
+    let mut keys = Keys::new(&[]);
+    for attr in &variant.attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                keys.insert(&meta.path)?;
+                attrs.rename = Some(meta.value()?.parse()?);
+            } else {
+                return Err(unknown_key(&meta.path));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(attrs)
+}
+
+fn resolved_field_attrs(field: &Field, struct_attrs: &StructAttrs) -> FieldAttrs {
+    let mut attrs = field_attrs(field).unwrap_or_default();
+    if let Some(except) = &struct_attrs.redact_all_except {
+        let excepted = field
+            .ident
+            .as_ref()
+            .is_some_and(|name| except.contains(name));
+        if !excepted && attrs.redact.is_none() {
+            attrs.redact = Some(Redact::Full);
+        }
+    }
+    if let (Some(name), Some(rename_all), None) =
+        (&field.ident, struct_attrs.rename_all, &attrs.rename)
+    {
+        let renamed = rename_all.apply(&name.unraw().to_string());
+        attrs.rename = Some(LitStr::new(&renamed, name.span()));
+    }
+    attrs
+}
+
+fn variant_debug(
+    path: TokenStream,
+    lit_name: &LitStr,
+    fields: &Fields,
+    struct_attrs: &StructAttrs,
+) -> TokenStream {
+    let debug_builder = match fields {
+        Fields::Named(_) => quote!(debug_struct),
+        Fields::Unnamed(_) => quote!(debug_tuple),
+        Fields::Unit => {
+            return quote! {
+                #path => f.write_str(#lit_name),
+            }
+        }
+    };
+    let (pattern, statements, exhaustive) =
+        field_statements(path, fields, struct_attrs, Sink::Builder);
+    let (non_exhaustive, finish) = match exhaustive {
+        Exhaustive::Yes => (None, quote!(builder.finish())),
+        Exhaustive::No => (None, quote!(builder.finish_non_exhaustive())),
+        Exhaustive::Runtime { skipped } => (
+            Some(quote!(let mut non_exhaustive = #skipped;)),
+            quote! {
+                if non_exhaustive {
+                    builder.finish_non_exhaustive()
+                } else {
+                    builder.finish()
+                }
+            },
+        ),
+    };
+
+    quote! {
+        #pattern => {
+            let builder = &mut f.#debug_builder(#lit_name);
+            #non_exhaustive
+            #(#statements)*
+            #finish
+        }
+    }
+}
+
+// Whether the output ends in `..`: always once a field is skipped, and decided
+// at runtime by `skip_if` and flattened fields otherwise.
+#[derive(Clone, Copy, PartialEq)]
+enum Exhaustive {
+    Yes,
+    No,
+    Runtime { skipped: bool },
+}
+
+// The pattern binding the fields of a struct or variant, and the statements
+// adding them to `builder` in output order.
+fn field_statements(
+    path: TokenStream,
+    fields: &Fields,
+    struct_attrs: &StructAttrs,
+    sink: Sink,
+) -> (TokenStream, Vec<TokenStream>, Exhaustive) {
+    let attrs: Vec<FieldAttrs> = fields
+        .iter()
+        .map(|field| resolved_field_attrs(field, struct_attrs))
+        .collect();
+    // Skipped fields are still bound if a shown field's `fmt` refers to them.
+    let referenced: Vec<Member> = attrs
+        .iter()
+        .filter(|attrs| !attrs.skip)
+        .filter_map(|attrs| attrs.fmt.as_ref())
+        .flat_map(|fmt| fmt.bound_args().1)
+        .collect();
+    let bindings: Vec<TokenStream> = fields
+        .iter()
+        .enumerate()
+        .zip(&attrs)
+        .map(|((i, field), attrs)| {
+            match attrs.skip && !referenced.contains(&field_member(i, field)) {
+                true => quote!(_),
+                false => field_binding(i, field).into_token_stream(),
+            }
+        })
+        .collect();
+    let pattern = match fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote!(#path { #(#names: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(#path(#(#bindings),*)),
+        Fields::Unit => path,
+    };
+
+    let mut shown: Vec<(usize, &Field, &FieldAttrs)> = fields
+        .iter()
+        .enumerate()
+        .zip(&attrs)
+        .filter(|(_, attrs)| !attrs.skip)
+        .map(|((i, field), attrs)| (i, field, attrs))
+        .collect();
+    if struct_attrs.sort_fields {
+        shown.sort_by_key(|(_, field, attrs)| field_name(field, attrs).map(|name| name.value()));
+    }
+    shown.sort_by_key(|(_, _, attrs)| attrs.order.unwrap_or(0));
+    let statements = shown
+        .into_iter()
+        .map(|(i, field, attrs)| field_debug(i, field, attrs, sink))
+        .collect();
+
+    let skipped = attrs.iter().any(|attrs| attrs.skip);
+    let exhaustive = if attrs
+        .iter()
+        .any(|attrs| attrs.skip_if.is_some() || attrs.flatten)
+    {
+        Exhaustive::Runtime { skipped }
+    } else if skipped {
+        Exhaustive::No
+    } else {
+        Exhaustive::Yes
+    };
+    (pattern, statements, exhaustive)
+}
+
+// With `visit`, an implementation of `DebugFields` passing the fields to a
+// visitor as they would appear in the Debug output.
+fn visit_fields(data: &Data, struct_attrs: &StructAttrs, helpers: &TokenStream) -> TokenStream {
+    let arm = |path: TokenStream, fields: &Fields| {
+        let (pattern, statements, _) = field_statements(path, fields, struct_attrs, Sink::Visitor);
+        quote! {
+            #pattern => {
+                #(#statements)*
+            }
+        }
+    };
+    let arms: Vec<TokenStream> = match data {
+        Data::Struct(data) => vec![arm(quote!(Self), &data.fields)],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                arm(quote!(Self::#ident), &variant.fields)
+            })
+            .collect(),
+        Data::Union(_) => unreachable!("rejected by derive"),
+    };
+    let body = if arms.is_empty() {
+        quote!(match *self {})
+    } else {
+        quote! {
+            match self {
+                #(#arms)*
+            }
+        }
+    };
+    quote! {
+        fn visit_fields(&self, visitor: &mut dyn ::derive_debug::FieldVisitor) {
+            #helpers
+            #body
+        }
+    }
+}
+
+// For structs with named fields, a hidden method adding the fields to another
+// type's `debug_struct`, called for fields marked `#[debug(flatten)]`.
+fn flatten_method(
+    data: &Data,
+    struct_attrs: &StructAttrs,
+    helpers: &TokenStream,
+) -> Option<TokenStream> {
+    let fields = match data {
+        Data::Struct(data) if matches!(data.fields, Fields::Named(_)) => &data.fields,
+        _ => return None,
+    };
+    if struct_attrs.fmt.is_some() || struct_attrs.transparent {
+        return None;
+    }
+    let (pattern, statements, exhaustive) =
+        field_statements(quote!(Self), fields, struct_attrs, Sink::Builder);
+    let non_exhaustive = match exhaustive {
+        Exhaustive::Yes => quote!(let non_exhaustive = false;),
+        Exhaustive::No => quote!(let non_exhaustive = true;),
+        Exhaustive::Runtime { skipped } => quote!(let mut non_exhaustive = #skipped;),
+    };
+    Some(quote! {
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        pub fn __debug_fields(&self, builder: &mut std::fmt::DebugStruct) -> bool {
+            #helpers
+            match self {
+                #pattern => {
+                    #non_exhaustive
+                    #(#statements)*
+                    non_exhaustive
+                }
+            }
+        }
+    })
+}
+
+fn template_arm(path: TokenStream, fmt: &LitStr) -> TokenStream {
+    let placeholders = parse_format(fmt).unwrap_or_default();
+    let value = fmt.value();
+    let mut format = String::new();
+    let mut members = Vec::new();
+    let mut last = 0;
+    for placeholder in &placeholders {
+        let member = placeholder.arg.member(fmt.span());
+        format.push_str(&value[last..placeholder.arg_range.start]);
+        format.push_str(&member_binding(&member).to_string());
+        last = placeholder.arg_range.end;
+        if !members.contains(&member) {
+            members.push(member);
+        }
+    }
+    format.push_str(&value[last..]);
+
+    let format = LitStr::new(&format, fmt.span());
+    let bindings: Vec<Ident> = members.iter().map(member_binding).collect();
+    quote! {
+        #path { #(#members: #bindings,)* .. } => {
+            std::write!(f, #format, #(#bindings = #bindings),*)
+        }
+    }
+}
+
+fn field_member(i: usize, field: &Field) -> Member {
+    match &field.ident {
+        Some(name) => Member::Named(name.clone()),
+        None => Member::Unnamed(i.into()),
+    }
+}
+
+fn field_binding(i: usize, field: &Field) -> Ident {
+    member_binding(&field_member(i, field))
+}
+
+fn member_binding(member: &Member) -> Ident {
+    match member {
+        Member::Named(name) => format_ident!("__self_{}", name),
+        Member::Unnamed(index) => format_ident!("__self_{}", index.index),
+    }
+}
+
+// A reference to the Debug value that stands in for a field.
+fn field_value(field: &Field, binding: &Ident, attrs: &FieldAttrs) -> TokenStream {
+    match (&attrs.redact, &attrs.with, &attrs.format) {
+        (Some(Redact::Full), _, _) => quote!(&std::format_args!("[REDACTED]")),
+        (Some(Redact::Partial), _, _) => {
+            quote!(&PartialRedaction(std::convert::AsRef::<str>::as_ref(#binding)))
+        }
+        (None, Some(with), _) => quote!(&DebugWith(#binding, #with)),
+        (None, None, Some(format)) => quote!(&std::format_args!(#format, #binding)),
+        (None, None, None) => match (&attrs.fmt, attrs.preset) {
+            (Some(fmt), _) => {
+                let format = &fmt.format;
+                let (args, _) = fmt.bound_args();
+                quote!(&std::format_args!(#format, #(#args),*))
+            }
+            (None, Some(preset)) => preset_debug(preset, &field.ty, binding),
+            (None, None) => quote!(&#binding),
+        },
+    }
+}
+
+fn transparent_arm(path: TokenStream, fields: &Fields, struct_attrs: &StructAttrs) -> TokenStream {
+    let (i, field, attrs) = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| (i, field, resolved_field_attrs(field, struct_attrs)))
+        .find(|(_, _, attrs)| !attrs.skip)
+        .expect("validated by struct_attrs");
+    let mut members = vec![field_member(i, field)];
+    if let Some(fmt) = &attrs.fmt {
+        for member in fmt.bound_args().1 {
+            if !members.contains(&member) {
+                members.push(member);
+            }
+        }
+    }
+    let bindings = members.iter().map(member_binding);
+    let value = field_value(field, &field_binding(i, field), &attrs);
+    quote! {
+        #path { #(#members: #bindings,)* .. } => std::fmt::Debug::fmt(#value, f),
+    }
+}
+
+fn field_name(field: &Field, attrs: &FieldAttrs) -> Option<LitStr> {
+    let name = field.ident.as_ref()?;
+    Some(
+        attrs
+            .rename
+            .clone()
+            .unwrap_or_else(|| LitStr::new(&name.unraw().to_string(), name.span())),
+    )
+}
+
+// Where field statements send the fields: a `builder` from `debug_struct` or
+// `debug_tuple`, or the `visitor` of `DebugFields::visit_fields`.
+#[derive(Clone, Copy)]
+enum Sink {
+    Builder,
+    Visitor,
+}
+
+fn field_debug(i: usize, field: &Field, attrs: &FieldAttrs, sink: Sink) -> TokenStream {
+    let binding = &field_binding(i, field);
+    let field_debug = match (sink, attrs.flatten) {
+        (Sink::Builder, true) => {
+            return quote_spanned! {field.ty.span()=>
+                if #binding.__debug_fields(builder) {
+                    non_exhaustive = true;
+                }
+            };
+        }
+        (Sink::Visitor, true) => quote_spanned! {field.ty.span()=>
+            ::derive_debug::DebugFields::visit_fields(#binding, visitor);
+        },
+        (Sink::Builder, false) => {
+            let value = field_value(field, binding, attrs);
+            match field_name(field, attrs) {
+                Some(lit_name) => quote! {
+                    builder.field(#lit_name, #value);
+                },
+                None => quote! {
+                    builder.field(#value);
+                },
+            }
+        }
+        (Sink::Visitor, false) => {
+            let value = field_value(field, binding, attrs);
+            let lit_name = field_name(field, attrs)
+                .unwrap_or_else(|| LitStr::new(&i.to_string(), Span::call_site()));
+            quote! {
+                visitor.field(#lit_name, #value);
+            }
+        }
+    };
+    match (&attrs.skip_if, sink) {
+        (Some(skip_if), Sink::Builder) => quote! {
+            if #skip_if(#binding) {
+                non_exhaustive = true;
+            } else {
+                #field_debug
+            }
+        },
+        (Some(skip_if), Sink::Visitor) => quote! {
+            if !#skip_if(#binding) {
+                #field_debug
+            }
+        },
+        (None, _) => field_debug,
+    }
+}
+
+fn preset_debug(preset: Preset, ty: &Type, binding: &Ident) -> TokenStream {
+    let (radix, radix_trait) = match preset {
+        Preset::Hex => ("{:#x}", quote!(std::fmt::LowerHex)),
+        Preset::Bin => ("{:#b}", quote!(std::fmt::Binary)),
+        Preset::Bytes => {
+            return quote! {
+                &DebugWith(#binding, |bytes, f| {
+                    f.write_str("b\"")?;
+                    for &byte in std::convert::AsRef::<[u8]>::as_ref(bytes) {
+                        std::fmt::Display::fmt(&std::ascii::escape_default(byte), f)?;
+                    }
+                    f.write_str("\"")
+                })
+            };
+        }
+        Preset::Truncate(len) => {
+            return quote! {
+                &DebugWith(#binding, |values, f| {
+                    let mut values = values.into_iter();
+                    let mut list = f.debug_list();
+                    list.entries(values.by_ref().take(#len));
+                    let more = values.count();
+                    if more > 0 {
+                        list.entry(&std::format_args!("… ({} more)", more));
+                    }
+                    list.finish()
+                })
+            };
+        }
+    };
+    // Which of the impls below applies is decided by method resolution, which
+    // tries `&&Radix` first, then `&Radix`, then `Radix`: a collection is
+    // iterated directly, or else through Deref as for `Arc<[u8]>`, and anything
+    // else is formatted as a single integer. `Scalar` always applies and only
+    // requires `Integer` once chosen, so a type that is neither reports the
+    // missing LowerHex or Binary impl at the field's type.
+    quote_spanned! {ty.span()=>
+        &DebugWith(#binding, |value, f| {
+            struct Radix<'a, C: ?Sized>(&'a C);
+
+            impl<C: ?Sized> std::clone::Clone for Radix<'_, C> {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl<C: ?Sized> std::marker::Copy for Radix<'_, C> {}
+
+            fn elements<'a, C: ?Sized>(
+                values: &'a C,
+                f: &mut std::fmt::Formatter,
+            ) -> std::fmt::Result
+            where
+                &'a C: std::iter::IntoIterator,
+                <&'a C as std::iter::IntoIterator>::Item: #radix_trait,
+            {
+                let mut list = f.debug_list();
+                for value in values {
+                    list.entry(&DebugWith(&value, |value, f| std::write!(f, #radix, value)));
+                }
+                list.finish()
+            }
+
+            trait Elements {
+                fn __radix(self, f: &mut std::fmt::Formatter) -> std::fmt::Result;
+            }
+
+            impl<'a, C: ?Sized> Elements for &&Radix<'a, C>
+            where
+                &'a C: std::iter::IntoIterator,
+                <&'a C as std::iter::IntoIterator>::Item: #radix_trait,
+            {
+                fn __radix(self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    elements(self.0, f)
+                }
+            }
+
+            trait DerefElements {
+                fn __radix(self, f: &mut std::fmt::Formatter) -> std::fmt::Result;
+            }
+
+            impl<'a, C: ?Sized + std::ops::Deref> DerefElements for &Radix<'a, C>
+            where
+                &'a C::Target: std::iter::IntoIterator,
+                <&'a C::Target as std::iter::IntoIterator>::Item: #radix_trait,
+            {
+                fn __radix(self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    elements(std::ops::Deref::deref(self.0), f)
+                }
+            }
+
+            trait Integer {
+                fn __integer(self, f: &mut std::fmt::Formatter) -> std::fmt::Result;
+            }
+
+            impl<C: ?Sized + #radix_trait> Integer for Radix<'_, C> {
+                fn __integer(self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    std::write!(f, #radix, self.0)
+                }
+            }
+
+            trait Scalar {
+                fn __radix(self, f: &mut std::fmt::Formatter) -> std::fmt::Result
+                where
+                    Self: Integer;
+            }
+
+            impl<C: ?Sized> Scalar for Radix<'_, C> {
+                fn __radix(self, f: &mut std::fmt::Formatter) -> std::fmt::Result
+                where
+                    Self: Integer,
+                {
+                    Integer::__integer(self, f)
+                }
+            }
+
+            (&&Radix(value)).__radix(f)
+        })
+    }
+}
+
+// With `remote`, the annotated type only mirrors the definition of a type from
+// another crate. Debug is implemented for a wrapper around a reference to the
+// remote type instead, obtained through `Mirror::debug_remote(&value)`.
+fn remote_debug(
+    vis: &Visibility,
+    name: &Ident,
+    remote: &Path,
+    generics: &Generics,
+    bounded: &Generics,
+    data: &Data,
+    body: TokenStream,
+) -> TokenStream {
+    let wrapper = format_ident!("{}DebugRemote", name);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let remote_ty = quote!(#remote #ty_generics);
+
+    let mut wrapper_generics = generics.clone();
+    wrapper_generics.params.insert(0, parse_quote!('__remote));
+    let wrapper_where = &wrapper_generics.where_clause;
+    let (_, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+
+    let mut debug_generics = bounded.clone();
+    debug_generics.params.insert(0, parse_quote!('__remote));
+    let (debug_impl_generics, _, debug_where_clause) = debug_generics.split_for_impl();
+
+    let check = remote_check(remote, data);
+
+    quote! {
+        #vis struct #wrapper #wrapper_generics (pub &'__remote #remote_ty) #wrapper_where;
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #vis fn debug_remote<'__remote>(
+                value: &'__remote #remote_ty,
+            ) -> #wrapper #wrapper_ty_generics {
+                #wrapper(value)
+            }
+        }
+
+        impl #debug_impl_generics std::fmt::Debug for #wrapper #wrapper_ty_generics #debug_where_clause {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                #check
+                #body
+            }
+        }
+    }
+}
+
+// Destructures the remote type exhaustively, with every field checked against
+// the type given for it in the mirror, so that a mirror which has fallen out
+// of date with the remote definition fails to compile.
+fn remote_check(remote: &Path, data: &Data) -> TokenStream {
+    let arm = |path: TokenStream, fields: &Fields| {
+        let bindings: Vec<Ident> = fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| field_binding(i, field))
+            .collect();
+        let types = fields.iter().map(|field| &field.ty);
+        let pattern = match fields {
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|field| &field.ident);
+                quote!(#path { #(#names: #bindings),* })
+            }
+            Fields::Unnamed(_) => quote!(#path(#(#bindings),*)),
+            Fields::Unit => path,
+        };
+        quote! {
+            #pattern => {
+                #(let _: &#types = #bindings;)*
+            }
+        }
+    };
+    let arms: Vec<TokenStream> = match data {
+        Data::Struct(data) => vec![arm(quote!(#remote), &data.fields)],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                arm(quote!(#remote::#ident), &variant.fields)
+            })
+            .collect(),
+        Data::Union(_) => Vec::new(),
+    };
+    if arms.is_empty() {
+        return TokenStream::new();
+    }
+    quote! {
+        match self.0 {
+            #(#arms)*
+        }
+    }
+}
+
+fn partial_redaction() -> TokenStream {
+    quote! {
+        struct PartialRedaction<'a>(&'a str);
+
+        impl std::fmt::Debug for PartialRedaction<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                const SHOWN: usize = 2;
+                let len = self.0.chars().count();
+                if len < 4 * SHOWN {
+                    return std::write!(f, "\"…\" ({} chars)", len);
+                }
+                let head = &self.0[..self.0.char_indices().nth(SHOWN).unwrap().0];
+                let tail = &self.0[self.0.char_indices().nth_back(SHOWN - 1).unwrap().0..];
+                std::write!(f, "\"{}…{}\" ({} chars)", head, tail, len)
+            }
+        }
+    }
+}
+
+// The DebugWith helper, if any field needs it.
+fn debug_with_helper(data: &Data) -> Option<TokenStream> {
+    fields(data)
+        .any(|field| {
+            field_attrs(field).is_ok_and(|attrs| attrs.with.is_some() || attrs.preset.is_some())
+        })
+        .then(debug_with)
+}
+
+fn debug_with() -> TokenStream {
+    quote! {
+        struct DebugWith<'a, T: ?Sized>(
+            &'a T,
+            fn(&T, &mut std::fmt::Formatter) -> std::fmt::Result,
+        );
+
+        impl<T: ?Sized> std::fmt::Debug for DebugWith<'_, T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                (self.1)(self.0, f)
+            }
+        }
+    }
+}
@@ -9,4 +9,30 @@ fn tests() {
     t.pass("examples/06-bound-trouble.rs");
     t.pass("examples/07-associated-type.rs");
     t.pass("examples/08-escape-hatch.rs");
+    t.pass("examples/09-enums-and-tuples.rs");
+    t.pass("examples/10-skip.rs");
+    t.pass("examples/11-redact.rs");
+    t.pass("examples/12-debug-with.rs");
+    t.pass("examples/13-attribute-grammar.rs");
+    t.compile_fail("examples/14-attribute-errors.rs");
+    t.compile_fail("examples/15-format-validation.rs");
+    t.pass("examples/16-fmt-expressions.rs");
+    t.pass("examples/17-rename.rs");
+    t.pass("examples/18-bounds.rs");
+    t.pass("examples/19-type-visitor.rs");
+    t.pass("examples/20-generic-params.rs");
+    t.pass("examples/21-display.rs");
+    t.compile_fail("examples/22-display-errors.rs");
+    t.pass("examples/23-presets.rs");
+    t.pass("examples/24-transparent.rs");
+    t.compile_fail("examples/25-transparent-errors.rs");
+    t.pass("examples/26-remote.rs");
+    t.compile_fail("examples/27-remote-mismatch.rs");
+    t.pass("examples/28-field-order.rs");
+    t.pass("examples/29-debug-diff.rs");
+    t.pass("examples/30-visit-fields.rs");
+    t.pass("examples/31-depth-and-cycles.rs");
+    t.pass("examples/32-phantom.rs");
+    t.compile_fail("examples/33-template-field-attrs.rs");
+    t.compile_fail("examples/34-preset-types.rs");
 }
@@ -0,0 +1,59 @@
+// Range bounds do not have to be plain unsigned literals. Negative numbers are
+// allowed, and so is constant arithmetic over integer literals using
+// `+ - * / % << >>` and parentheses, with the usual Rust precedence. The bounds
+// are evaluated while the macro expands, over every integer from i128::MIN to
+// u128::MAX so that bounds of either type can be written.
+
+use seq::seq;
+
+const fn offsets() -> [i32; 8] {
+    let mut offsets = [0; 8];
+    seq!(N in -4..4 {
+        offsets[(N + 4) as usize] = N * 10;
+    });
+    offsets
+}
+
+seq!(N in 0..1 << 2 {
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Flag {
+        #(
+            Bit~N = 1 << N,
+        )*
+    }
+});
+
+fn main() {
+    assert_eq!(offsets(), [-40, -30, -20, -10, 0, 10, 20, 30]);
+
+    assert_eq!(Flag::Bit3 as u8, 8);
+
+    let mut sum = 0;
+    seq!(N in -(2 + 1) * 2 ..= (100 - 94) / 2 % 4 {
+        sum += N;
+    });
+    assert_eq!(sum, (-6..=3).sum());
+
+    let mut count = 0u32;
+    seq!(N in 170141183460469231731687303715884105720..=170141183460469231731687303715884105726 {
+        count += 1;
+        let _: i128 = N;
+    });
+    assert_eq!(count, 7);
+
+    let top = seq!(N in 340282366920938463463374607431768211453..=340282366920938463463374607431768211455 {
+        [#(N,)*]
+    });
+    assert_eq!(top, [u128::MAX - 2, u128::MAX - 1, u128::MAX]);
+
+    let bottom = seq!(N in -170141183460469231731687303715884105728..-(1 << 127) + 2 {
+        [#(N,)*]
+    });
+    assert_eq!(bottom, [i128::MIN, i128::MIN + 1]);
+
+    let shifted = seq!(N in (1 << 127) - 1..=(1 << 127) { [#(N,)*] });
+    assert_eq!(shifted, [i128::MAX as u128, 1 << 127]);
+
+    let rounded = seq!(N in -7 >> 1..=-7 / 2 { [#(N,)*] });
+    assert_eq!(rounded, [-4, -3]);
+}
@@ -0,0 +1,25 @@
+// Bounds are evaluated with checked arithmetic, so anything that would
+// overflow, divide by zero or shift out of range is a compile error pointing at
+// the operator responsible. Bounds may lie anywhere from i128::MIN to
+// u128::MAX, but a range may not hold more values than a u128 can count.
+// Negative numbers cannot be pasted into identifiers.
+
+use seq::seq;
+
+seq!(N in 0..1 << 128 {});
+
+seq!(N in 0..340282366920938463463374607431768211456 {});
+
+seq!(N in 0..-170141183460469231731687303715884105728 - 1 {});
+
+seq!(N in 0..(4 - 4) / 0 {});
+
+seq!(N in -1..=340282366920938463463374607431768211455 {});
+
+seq!(N in -(1 << 127)..340282366920938463463374607431768211455 {});
+
+seq!(N in -2..0 {
+    struct Negative~N;
+});
+
+fn main() {}
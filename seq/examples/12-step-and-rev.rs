@@ -0,0 +1,73 @@
+// The range can be wrapped in parentheses and followed by the iterator adapters
+// `.step_by(N)` and `.rev()`, which apply in the order they are written just
+// like they would on a real iterator. This suits register tables laid out at a
+// fixed stride, or anything that has to be emitted in descending order.
+
+use seq::seq;
+
+seq!(N in (0..64).step_by(8) {
+    #[allow(dead_code)]
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Register {
+        #(
+            Reg~N = N,
+        )*
+    }
+});
+
+fn main() {
+    assert_eq!(Register::Reg8 as u8, 8);
+    assert_eq!(Register::Reg56 as u8, 56);
+
+    let countdown = seq!(N in (1..=5).rev() { [#(N,)*] });
+    assert_eq!(countdown, [5, 4, 3, 2, 1]);
+
+    let mut order = Vec::new();
+    seq!(N in (0..10).rev().step_by(3) {
+        order.push(N);
+    });
+    assert_eq!(order, [9, 6, 3, 0]);
+
+    let mut order = Vec::new();
+    seq!(N in (0..10).step_by(3).rev() {
+        order.push(N);
+    });
+    assert_eq!(order, [9, 6, 3, 0]);
+
+    let mut order = Vec::new();
+    seq!(N in (0..=1 << 4).step_by(5).rev() {
+        order.push(N);
+    });
+    assert_eq!(order, [15, 10, 5, 0]);
+
+    // Wide ranges with a large stride only cost as much as the values they
+    // actually produce.
+    let mut count = 0;
+    seq!(N in (0..1 << 40).step_by(1 << 36) {
+        count += 1;
+        let _: i64 = N;
+    });
+    assert_eq!(count, 16);
+
+    let windows = seq!(N in (0x4000_0000..=0x7000_0000).step_by(0x1000_0000).rev() { [#(N,)*] });
+    assert_eq!(
+        windows,
+        [0x7000_0000, 0x6000_0000, 0x5000_0000, 0x4000_0000]
+    );
+
+    let extremes = seq!(N in (-((1 << 126) - 1) * 2 - 1..((1 << 126) - 1) * 2 + 1).step_by(1 << 126).rev() {
+        [#(N,)*]
+    });
+    assert_eq!(
+        extremes,
+        [(1 << 126) + 1, 1, -(1 << 126) + 1, i128::MIN + 1]
+    );
+
+    seq!(N in 0..18446744073709551615u64 {});
+
+    let mut sum = 0;
+    seq!(N in (2 + 1)..(1 << 3) {
+        sum += N;
+    });
+    assert_eq!(sum, 3 + 4 + 5 + 6 + 7);
+}
@@ -0,0 +1,37 @@
+// Several loop variables can be declared in one invocation, separated by
+// commas. They advance in lockstep, like iterators combined with `zip`, and
+// every one of them can be substituted and pasted into identifiers.
+//
+// This pairs register indices with their addresses without nesting seq!
+// invocations.
+
+use seq::seq;
+
+seq!(N in 0..4, ADDR in (0x40..0x60).step_by(8) {
+    #(
+        const REG~N: u32 = ADDR;
+    )*
+
+    static TABLE: [(u8, u32); 4] = [#((N, ADDR),)*];
+});
+
+seq!(I in 0..3, J in (0..3).rev(), K in -1..2 {
+    fn pairs() -> Vec<(i32, i32, i32)> {
+        vec![#((I, J, K),)*]
+    }
+});
+
+fn main() {
+    assert_eq!(REG0, 0x40);
+    assert_eq!(REG3, 0x58);
+    assert_eq!(TABLE[2], (2, 0x50));
+
+    assert_eq!(pairs(), [(0, 2, -1), (1, 1, 0), (2, 0, 1)]);
+
+    let mut names = Vec::new();
+    seq!(A in 1..=2, B in 10..12 {
+        let sum~B = A + B;
+        names.push(sum~B);
+    });
+    assert_eq!(names, [11, 13]);
+}
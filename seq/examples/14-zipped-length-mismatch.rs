@@ -0,0 +1,11 @@
+// Zipped loop variables must take the same number of values, since there is no
+// sensible value to substitute for the shorter one once it runs out. The error
+// points at the variable whose range disagrees with the first one.
+
+use seq::seq;
+
+seq!(N in 0..4, M in 10..=14 {});
+
+seq!(N in 0..4, N in 4..8 {});
+
+fn main() {}
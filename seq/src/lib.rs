@@ -1,70 +1,484 @@
 use proc_macro2::{Delimiter, Group, Literal, Spacing, TokenStream, TokenTree};
 use quote::{format_ident, TokenStreamExt};
-use std::ops::Range;
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
 use syn::parse::{Parse, ParseStream};
-use syn::{braced, parse_macro_input, token, Ident, LitInt, Result, Token};
+use syn::{braced, parenthesized, parse_macro_input, token, Error, Ident, LitInt, Result, Token};
 
 #[derive(Debug)]
 struct Sequence {
-    ident: Ident,
-    _in_token: Token![in],
-    start: usize,
-    _exclusive_range: Option<Token![..]>,
-    _inclusive_range: Option<Token![..=]>,
-    end: usize,
+    variables: Vec<Variable>,
     _brace_token: token::Brace,
     content: TokenStream,
 }
 
+#[derive(Debug)]
+struct Variable {
+    ident: Ident,
+    _in_token: Token![in],
+    values: Values,
+}
+
+// The values a loop variable takes: the one at `first` plus `last_index` more,
+// `step` apart and counting down if `descending`, or none at all. Adapters
+// adjust this description instead of a list of values, so a wide range with a
+// large stride stays cheap.
+#[derive(Debug, Clone)]
+struct Values {
+    first: Int,
+    step: u128,
+    descending: bool,
+    last_index: Option<u128>,
+}
+
+impl Values {
+    // None if the range holds more values than a u128 can count.
+    fn new(start: Int, end: Int, is_inclusive: bool) -> Option<Self> {
+        let last = match is_inclusive {
+            true => Some(end),
+            false => end.checked_sub(Int::ONE),
+        };
+        let last_index = match last {
+            Some(last) if start <= last => Some(last.checked_sub(start)?.magnitude),
+            _ => None,
+        };
+        Some(Values {
+            first: start,
+            step: 1,
+            descending: false,
+            last_index,
+        })
+    }
+
+    fn len(&self) -> String {
+        match self.last_index {
+            Some(last_index) => match last_index.checked_add(1) {
+                Some(len) => len.to_string(),
+                None => "2^128".to_owned(),
+            },
+            None => "0".to_owned(),
+        }
+    }
+
+    fn stride(&mut self, step: u128) {
+        self.last_index = self.last_index.map(|last_index| last_index / step);
+        // Only saturates once a single value is left, when the step is unused.
+        self.step = self.step.saturating_mul(step);
+    }
+
+    fn reverse(&mut self) {
+        if let Some(last_index) = self.last_index {
+            self.first = self.advance(last_index * self.step);
+        }
+        self.descending = !self.descending;
+    }
+
+    fn advance(&self, distance: u128) -> Int {
+        let distance = Int::new(self.descending, distance);
+        distance
+            .and_then(|distance| self.first.checked_add(distance))
+            .expect("distance stays within the range")
+    }
+}
+
+impl Iterator for Values {
+    type Item = Int;
+
+    fn next(&mut self) -> Option<Int> {
+        let value = self.first;
+        self.last_index = match self.last_index? {
+            0 => None,
+            last_index => {
+                self.first = self.advance(self.step);
+                Some(last_index - 1)
+            }
+        };
+        Some(value)
+    }
+}
+
+// An integer anywhere from i128::MIN to u128::MAX, so that bounds of either
+// type can be written and mixed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Int {
+    negative: bool,
+    magnitude: u128,
+}
+
+impl Int {
+    const ONE: Int = Int {
+        negative: false,
+        magnitude: 1,
+    };
+
+    fn new(negative: bool, magnitude: u128) -> Option<Self> {
+        match negative && magnitude > 1 << 127 {
+            true => None,
+            false => Some(Int {
+                negative: negative && magnitude != 0,
+                magnitude,
+            }),
+        }
+    }
+
+    fn checked_neg(self) -> Option<Self> {
+        Int::new(!self.negative, self.magnitude)
+    }
+
+    fn checked_add(self, rhs: Int) -> Option<Self> {
+        if self.negative == rhs.negative {
+            Int::new(self.negative, self.magnitude.checked_add(rhs.magnitude)?)
+        } else if self.magnitude >= rhs.magnitude {
+            Int::new(self.negative, self.magnitude - rhs.magnitude)
+        } else {
+            Int::new(rhs.negative, rhs.magnitude - self.magnitude)
+        }
+    }
+
+    fn checked_sub(self, rhs: Int) -> Option<Self> {
+        // Negating rhs first would overflow for rhs above i128::MAX.
+        let rhs = Int {
+            negative: !rhs.negative,
+            magnitude: rhs.magnitude,
+        };
+        self.checked_add(rhs)
+    }
+
+    fn checked_mul(self, rhs: Int) -> Option<Self> {
+        let magnitude = self.magnitude.checked_mul(rhs.magnitude)?;
+        Int::new(self.negative != rhs.negative, magnitude)
+    }
+
+    // Rounds toward zero, as Rust's `/` and `%` do.
+    fn checked_div(self, rhs: Int) -> Option<Self> {
+        let magnitude = self.magnitude.checked_div(rhs.magnitude)?;
+        Int::new(self.negative != rhs.negative, magnitude)
+    }
+
+    fn checked_rem(self, rhs: Int) -> Option<Self> {
+        Int::new(self.negative, self.magnitude.checked_rem(rhs.magnitude)?)
+    }
+
+    fn checked_shl(self, rhs: Int) -> Option<Self> {
+        let shift = self.shift(rhs)?;
+        let magnitude = self.magnitude << shift;
+        match magnitude >> shift == self.magnitude {
+            true => Int::new(self.negative, magnitude),
+            false => None,
+        }
+    }
+
+    // Rounds toward negative infinity, as Rust's `>>` does on signed integers.
+    fn checked_shr(self, rhs: Int) -> Option<Self> {
+        let shift = self.shift(rhs)?;
+        let mut magnitude = self.magnitude >> shift;
+        if self.negative && magnitude << shift != self.magnitude {
+            magnitude += 1;
+        }
+        Int::new(self.negative, magnitude)
+    }
+
+    fn shift(self, rhs: Int) -> Option<u32> {
+        match rhs.negative {
+            true => None,
+            false => u32::try_from(rhs.magnitude)
+                .ok()
+                .filter(|&shift| shift < 128),
+        }
+    }
+
+    fn literal(self) -> Literal {
+        match self.negative {
+            true => Literal::i128_unsuffixed((self.magnitude as i128).wrapping_neg()),
+            false => Literal::u128_unsuffixed(self.magnitude),
+        }
+    }
+}
+
+impl Ord for Int {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for Int {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Int {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.negative {
+            true => write!(f, "-{}", self.magnitude),
+            false => write!(f, "{}", self.magnitude),
+        }
+    }
+}
+
 impl Parse for Sequence {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut variables = vec![input.parse::<Variable>()?];
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            let variable = input.parse::<Variable>()?;
+            if let Some(first) = variables.iter().find(|first| first.ident == variable.ident) {
+                return Err(Error::new(
+                    variable.ident.span(),
+                    format!("`{}` is already a loop variable", first.ident),
+                ));
+            }
+            if variable.values.last_index != variables[0].values.last_index {
+                return Err(Error::new(
+                    variable.ident.span(),
+                    format!(
+                        "`{}` takes {} values but `{}` takes {}; zipped loop variables must \
+                         take the same number of values",
+                        variable.ident,
+                        variable.values.len(),
+                        variables[0].ident,
+                        variables[0].values.len(),
+                    ),
+                ));
+            }
+            variables.push(variable);
+        }
+        let content;
+        Ok(Sequence {
+            variables,
+            _brace_token: braced!(content in input),
+            content: content.parse::<TokenStream>()?,
+        })
+    }
+}
+
+impl Parse for Variable {
     fn parse(input: ParseStream) -> Result<Self> {
         let ident = input.parse()?;
         let _in_token = input.parse()?;
-        let start = input.parse::<LitInt>()?.base10_parse()?;
-        let is_inclusive = input.peek(Token![..=]);
-        let (_exclusive_range, _inclusive_range) = match is_inclusive {
-            true => (None, Some(input.parse()?)),
-            false => (Some(input.parse()?), None),
+        let values = match has_adapters(input) {
+            true => adapted_range(input)?,
+            false => range(input)?,
         };
-        let end = input.parse::<LitInt>()?.base10_parse::<usize>()? + is_inclusive as usize;
-        let content;
-        Ok(Sequence {
+        Ok(Variable {
             ident,
             _in_token,
-            start,
-            _exclusive_range,
-            _inclusive_range,
-            end,
-            _brace_token: braced!(content in input),
-            content: content.parse::<TokenStream>()?,
+            values,
         })
     }
 }
 
+// A parenthesized range followed by `.`, as in `(0..8).rev()`, rather than a
+// parenthesized bound like `(1 + 1)..8`.
+fn has_adapters(input: ParseStream) -> bool {
+    let fork = input.fork();
+    matches!(
+        fork.parse::<TokenTree>(),
+        Ok(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis
+    ) && !fork.peek(Token![..])
+        && fork.peek(Token![.])
+}
+
+fn range(input: ParseStream) -> Result<Values> {
+    let span = input.span();
+    let start = bound(input, 0)?;
+    let is_inclusive = input.peek(Token![..=]);
+    match is_inclusive {
+        true => drop(input.parse::<Token![..=]>()?),
+        false => drop(input.parse::<Token![..]>()?),
+    }
+    let end = bound(input, 0)?;
+    Values::new(start, end, is_inclusive).ok_or_else(|| {
+        let op = if is_inclusive { "..=" } else { ".." };
+        let message = format!(
+            "range {}{}{} has more values than a u128 can count",
+            start, op, end
+        );
+        Error::new(span, message)
+    })
+}
+
+// Applies `.step_by(N)` and `.rev()` to the range in the order they are
+// written, like the iterator adapters of the same name.
+fn adapted_range(input: ParseStream) -> Result<Values> {
+    let content;
+    parenthesized!(content in input);
+    let mut values = range(&content)?;
+    if !content.is_empty() {
+        return Err(content.error("unexpected token after range"));
+    }
+
+    while input.peek(Token![.]) {
+        input.parse::<Token![.]>()?;
+        let adapter = input.parse::<Ident>()?;
+        let arguments;
+        parenthesized!(arguments in input);
+        if adapter == "rev" {
+            if !arguments.is_empty() {
+                return Err(arguments.error("`rev` takes no arguments"));
+            }
+            values.reverse();
+        } else if adapter == "step_by" {
+            let span = arguments.span();
+            let step = bound(&arguments, 0)?;
+            if !arguments.is_empty() {
+                return Err(arguments.error("`step_by` takes one argument"));
+            }
+            if step.negative || step.magnitude == 0 {
+                return Err(Error::new(span, "step must be a positive integer"));
+            }
+            values.stride(step.magnitude);
+        } else {
+            return Err(Error::new(adapter.span(), "expected `step_by` or `rev`"));
+        }
+    }
+    Ok(values)
+}
+
+// Evaluates a range bound made of integer literals, unary minus, parentheses
+// and the binary operators `* / % + - << >>`, which bind in that order like
+// they do in Rust. Operators binding looser than `min_precedence` are left for
+// the caller.
+fn bound(input: ParseStream, min_precedence: u8) -> Result<Int> {
+    let mut lhs = operand(input)?;
+    while let Some((op, precedence)) = binary_operator(input) {
+        if precedence < min_precedence {
+            break;
+        }
+        let span = input.span();
+        match op {
+            "<<" => drop(input.parse::<Token![<<]>()?),
+            ">>" => drop(input.parse::<Token![>>]>()?),
+            "+" => drop(input.parse::<Token![+]>()?),
+            "-" => drop(input.parse::<Token![-]>()?),
+            "*" => drop(input.parse::<Token![*]>()?),
+            "/" => drop(input.parse::<Token![/]>()?),
+            _ => drop(input.parse::<Token![%]>()?),
+        }
+        let rhs = bound(input, precedence + 1)?;
+        lhs = evaluate(lhs, op, rhs).map_err(|message| Error::new(span, message))?;
+    }
+    Ok(lhs)
+}
+
+fn binary_operator(input: ParseStream) -> Option<(&'static str, u8)> {
+    if input.peek(Token![<<]) {
+        Some(("<<", 0))
+    } else if input.peek(Token![>>]) {
+        Some((">>", 0))
+    } else if input.peek(Token![+]) {
+        Some(("+", 1))
+    } else if input.peek(Token![-]) {
+        Some(("-", 1))
+    } else if input.peek(Token![*]) {
+        Some(("*", 2))
+    } else if input.peek(Token![/]) {
+        Some(("/", 2))
+    } else if input.peek(Token![%]) {
+        Some(("%", 2))
+    } else {
+        None
+    }
+}
+
+fn operand(input: ParseStream) -> Result<Int> {
+    if input.peek(Token![-]) {
+        let minus = input.parse::<Token![-]>()?;
+        let value = operand(input)?;
+        return value.checked_neg().ok_or_else(|| {
+            Error::new(
+                minus.span,
+                format!("attempt to negate `{}`, which would overflow", value),
+            )
+        });
+    }
+    if input.peek(token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        let value = bound(&content, 0)?;
+        if !content.is_empty() {
+            return Err(content.error("expected an operator"));
+        }
+        return Ok(value);
+    }
+    let lit = input.parse::<LitInt>()?;
+    let magnitude = lit
+        .base10_parse::<u128>()
+        .map_err(|_| Error::new(lit.span(), "integer literal is too large"))?;
+    Ok(Int {
+        negative: false,
+        magnitude,
+    })
+}
+
+fn evaluate(lhs: Int, op: &str, rhs: Int) -> std::result::Result<Int, String> {
+    let value = match op {
+        "+" => lhs.checked_add(rhs),
+        "-" => lhs.checked_sub(rhs),
+        "*" => lhs.checked_mul(rhs),
+        "/" if rhs.magnitude == 0 => return Err(format!("attempt to divide `{}` by zero", lhs)),
+        "/" => lhs.checked_div(rhs),
+        "%" if rhs.magnitude == 0 => {
+            return Err(format!(
+                "attempt to calculate the remainder of `{}` with a divisor of zero",
+                lhs
+            ))
+        }
+        "%" => lhs.checked_rem(rhs),
+        "<<" => lhs.checked_shl(rhs),
+        _ => lhs.checked_shr(rhs),
+    };
+    value.ok_or_else(|| {
+        format!(
+            "attempt to compute `{} {} {}`, which would overflow",
+            lhs, op, rhs
+        )
+    })
+}
+
 #[proc_macro]
 pub fn seq(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let sequence = parse_macro_input!(input as Sequence);
 
     let Sequence {
-        ident,
-        start,
-        end,
-        content,
-        ..
+        variables, content, ..
     } = sequence;
 
     let mut stream = TokenStream::new();
 
-    if has_repeat_annotation(content.clone()) {
-        stream = repeat(content.clone(), &ident, start..end);
+    let expanded = if has_repeat_annotation(content.clone()) {
+        repeat(content.clone(), &variables).map(|repeated| stream = repeated)
+    } else if content.is_empty() {
+        Ok(())
     } else {
-        for i in start..end {
-            let substitution = Literal::usize_unsuffixed(i);
-            stream.append_all(substitute(content.clone(), &ident, &substitution));
-        }
+        steps(&variables).try_for_each(|substitutions| {
+            stream.append_all(substitute(content.clone(), &substitutions)?);
+            Ok(())
+        })
+    };
+
+    match expanded {
+        Ok(()) => stream.into(),
+        Err(err) => err.to_compile_error().into(),
     }
+}
 
-    stream.into()
+// One list of (variable, value) pairs per step, all variables advancing
+// together.
+fn steps(variables: &[Variable]) -> impl Iterator<Item = Vec<(&Ident, Literal)>> {
+    let mut values = Vec::from_iter(variables.iter().map(|variable| variable.values.clone()));
+    std::iter::from_fn(move || {
+        values
+            .iter_mut()
+            .zip(variables)
+            .map(|(values, variable)| Some((&variable.ident, values.next()?.literal())))
+            .collect()
+    })
 }
 
 fn has_repeat_annotation(stream: TokenStream) -> bool {
@@ -98,7 +512,7 @@ fn repeat_annotation(tokens: &[TokenTree], i: usize) -> Option<TokenStream> {
     None
 }
 
-fn repeat(stream: TokenStream, victim: &Ident, sequence: Range<usize>) -> TokenStream {
+fn repeat(stream: TokenStream, variables: &[Variable]) -> Result<TokenStream> {
     let tokens = Vec::from_iter(stream);
 
     let mut output = Vec::<TokenTree>::with_capacity(tokens.len());
@@ -106,12 +520,13 @@ fn repeat(stream: TokenStream, victim: &Ident, sequence: Range<usize>) -> TokenS
     let mut i = 0;
     while i < tokens.len() {
         if let TokenTree::Group(group) = &tokens[i] {
-            let group_stream = repeat(group.stream(), victim, sequence.clone());
+            let group_stream = repeat(group.stream(), variables)?;
             output.push(Group::new(group.delimiter(), group_stream).into());
         } else if let Some(group_stream) = repeat_annotation(tokens.as_slice(), i) {
-            for j in sequence.clone() {
-                let substitution = Literal::usize_unsuffixed(j);
-                output.extend(substitute(group_stream.clone(), victim, &substitution));
+            if !group_stream.is_empty() {
+                for substitutions in steps(variables) {
+                    output.extend(substitute(group_stream.clone(), &substitutions)?);
+                }
             }
             i += 2;
         } else {
@@ -120,10 +535,17 @@ fn repeat(stream: TokenStream, victim: &Ident, sequence: Range<usize>) -> TokenS
         i += 1;
     }
 
-    TokenStream::from_iter(output)
+    Ok(TokenStream::from_iter(output))
 }
 
-fn substitute(stream: TokenStream, victim: &Ident, substitution: &Literal) -> TokenStream {
+fn substitute(stream: TokenStream, substitutions: &[(&Ident, Literal)]) -> Result<TokenStream> {
+    let substitution_for = |ident: &Ident| {
+        substitutions
+            .iter()
+            .find(|(victim, _)| *victim == ident)
+            .map(|(_, substitution)| substitution)
+    };
+
     let mut tokens = Vec::from_iter(stream.into_iter().map(Some));
 
     let mut i = 0;
@@ -131,31 +553,38 @@ fn substitute(stream: TokenStream, victim: &Ident, substitution: &Literal) -> To
         let token = &tokens[i];
         match token {
             Some(TokenTree::Group(group)) => {
-                let stream = substitute(group.stream(), victim, substitution);
+                let stream = substitute(group.stream(), substitutions)?;
                 let group = proc_macro2::Group::new(group.delimiter(), stream);
                 tokens[i] = Some(group.into())
             }
             Some(TokenTree::Ident(ident)) => {
-                if ident == victim {
+                if let Some(substitution) = substitution_for(ident) {
                     let mut substitution = substitution.clone();
                     substitution.set_span(ident.span());
                     tokens[i] = Some(substitution.into());
                 }
             }
-            Some(TokenTree::Punct(punct)) => {
-                if punct.as_char() == '~' && punct.spacing() == Spacing::Alone {
-                    if let Some(slice) = tokens.get(i - 1..i + 2) {
-                        if let Ok(
-                            [Some(TokenTree::Ident(start)), .., Some(TokenTree::Ident(end))],
-                        ) = <&[Option<TokenTree>; 3]>::try_from(slice)
-                        {
-                            if end == victim {
-                                let ident =
-                                    format_ident!("{start}{substitution}", span = start.span());
-                                tokens[i - 1] = Some(ident.into());
-                                tokens[i] = None;
-                                tokens[i + 1] = None;
+            Some(TokenTree::Punct(punct))
+                if punct.as_char() == '~' && punct.spacing() == Spacing::Alone && i > 0 =>
+            {
+                if let Some(slice) = tokens.get(i - 1..i + 2) {
+                    if let Ok([Some(TokenTree::Ident(start)), .., Some(TokenTree::Ident(end))]) =
+                        <&[Option<TokenTree>; 3]>::try_from(slice)
+                    {
+                        if let Some(substitution) = substitution_for(end) {
+                            if substitution.to_string().starts_with('-') {
+                                return Err(Error::new(
+                                    end.span(),
+                                    format!(
+                                        "cannot paste negative number `{}` into an identifier",
+                                        substitution
+                                    ),
+                                ));
                             }
+                            let ident = format_ident!("{start}{substitution}", span = start.span());
+                            tokens[i - 1] = Some(ident.into());
+                            tokens[i] = None;
+                            tokens[i + 1] = None;
                         }
                     }
                 }
@@ -165,5 +594,5 @@ fn substitute(stream: TokenStream, victim: &Ident, substitution: &Literal) -> To
         i += 1;
     }
 
-    TokenStream::from_iter(tokens.into_iter().flatten())
+    Ok(TokenStream::from_iter(tokens.into_iter().flatten()))
 }
@@ -10,4 +10,9 @@ fn tests() {
     t.pass("examples/07-inclusive-range.rs");
     t.compile_fail("examples/08-ident-span.rs");
     t.pass("examples/09-interaction-with-macrorules.rs");
+    t.pass("examples/10-signed-and-const-bounds.rs");
+    t.compile_fail("examples/11-bound-overflow.rs");
+    t.pass("examples/12-step-and-rev.rs");
+    t.pass("examples/13-zipped-variables.rs");
+    t.compile_fail("examples/14-zipped-length-mismatch.rs");
 }